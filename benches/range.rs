@@ -0,0 +1,37 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use semver_rs::{Options, Range};
+
+// ranges collected from the dependencies of popular npm packages
+const RANGES: &str = include_str!("../bench/ranges.txt");
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let ranges = RANGES.lines().collect::<Vec<_>>();
+    let loose = Options::builder().loose(true).build();
+
+    c.bench_function("Range ^1.2.3", |b| {
+        b.iter(|| black_box(Range::new("^1.2.3").parse().ok()))
+    });
+    c.bench_function("Range >=1.2.3 <2.0.0 || 3.x", |b| {
+        b.iter(|| black_box(Range::new(">=1.2.3 <2.0.0 || 3.x").parse().ok()))
+    });
+    c.bench_function("Range 1.2.3 - 2.3.4", |b| {
+        b.iter(|| black_box(Range::new("1.2.3 - 2.3.4").parse().ok()))
+    });
+    c.bench_function("Range npm corpus", |b| {
+        b.iter(|| {
+            for range in ranges.iter() {
+                black_box(Range::new(range).parse().ok());
+            }
+        })
+    });
+    c.bench_function("Range npm corpus loose", |b| {
+        b.iter(|| {
+            for range in ranges.iter() {
+                black_box(Range::new(range).with_options(loose).parse().ok());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -1,10 +1,46 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use semver_rs::Version;
+use semver_rs::{Range, Version};
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("Version 2.0.0", |b| {
         b.iter(|| black_box(Version::new("2.0.0").parse().ok()))
     });
+
+    // the history of a package, with a few prereleases before each minor
+    let mut versions = vec![];
+    for major in 0..4 {
+        for minor in 0..10 {
+            for pre in ["alpha.1", "beta.1", "rc.1"].iter() {
+                versions.push(format!("{}.{}.0-{}", major, minor, pre));
+            }
+            for patch in 0..10 {
+                versions.push(format!("{}.{}.{}", major, minor, patch));
+            }
+        }
+    }
+    let versions = versions
+        .iter()
+        .map(|v| Version::new(v).parse().unwrap())
+        .collect::<Vec<_>>();
+    let range = Range::new(">=1.2.3 <2.0.0 || ~2.4.0-beta.1 || 3.x")
+        .parse()
+        .unwrap();
+    let compiled = range.compile();
+
+    c.bench_function("Range::test history", |b| {
+        b.iter(|| {
+            for v in versions.iter() {
+                black_box(range.test(v));
+            }
+        })
+    });
+    c.bench_function("CompiledRange::test history", |b| {
+        b.iter(|| {
+            for v in versions.iter() {
+                black_box(compiled.test(v));
+            }
+        })
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -1,4 +1,7 @@
 use crate::error::Error;
+use crate::expressions::{MAX_LENGTH, MAX_SAFE_INTEGER};
+use crate::util::version_len;
+use crate::version::Version;
 
 use std::marker::PhantomData;
 
@@ -29,25 +32,37 @@ impl OptionsBuilder {
         self
     }
 
+    /// Sets the `max_length` option. Refer to [Options.max_length](crate::Options::max_length).
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.opts.max_length = max_length;
+        self
+    }
+
+    /// Sets the `max_safe_integer` option. Refer to [Options.max_safe_integer](crate::Options::max_safe_integer).
+    pub fn max_safe_integer(mut self, max_safe_integer: u64) -> Self {
+        self.opts.max_safe_integer = max_safe_integer;
+        self
+    }
+
     pub fn build(self) -> Options {
         self.opts
     }
 }
 
 /// Allows to configure the parsing of semver strings, same as the [node-semver](https://github.com/npm/node-semver#functions) package.
-/// All options are false by default.
+/// All flags are false by default, while the limits default to the ones of node-semver.
 /// ## Example
 /// ```
 /// # use semver_rs::{Options, Version, Error};
 /// let opts = Options::builder().loose(true).include_prerelease(true).build();
 /// //or
-/// let opts = Options { loose: true, include_prerelease: true };
+/// let opts = Options { loose: true, include_prerelease: true, ..Default::default() };
 ///
 /// Version::new("1.2.3").with_options(opts).parse()?;
 /// # Ok::<(), Error>(())
 /// ```
-#[derive(Default, Clone, Debug, Copy)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Clone, Debug, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(default))]
 pub struct Options {
     /// Be more forgiving about not-quite-valid semver strings.
     /// Any resulting output will always be 100% strict compliant.
@@ -56,6 +71,26 @@ pub struct Options {
     /// Set to suppress the [default behavior](https://github.com/npm/node-semver#prerelease-tags) of excluding prerelease tagged
     /// versions from ranges unless they are explicitly opted into.
     pub include_prerelease: bool,
+
+    /// The maximum length of a version, `256` by default. Longer versions are rejected with
+    /// [Error::TooLong](crate::Error::TooLong), and so are ranges with a comparator whose version is longer.
+    pub max_length: usize,
+
+    /// The largest major, minor or patch number of a version, `2^53 - 1` by default, the largest integer
+    /// a JavaScript number can exactly represent. Larger ones are rejected with [Error::TooLarge](crate::Error::TooLarge),
+    /// including the ones ranges desugar to, e.g. `<9007199254740992.0.0` for `^9007199254740991`.
+    pub max_safe_integer: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            loose: false,
+            include_prerelease: false,
+            max_length: MAX_LENGTH,
+            max_safe_integer: MAX_SAFE_INTEGER,
+        }
+    }
 }
 
 pub trait IntoOptionsMaybe: Into<Option<Options>> + Clone + Copy + Default {}
@@ -66,12 +101,64 @@ impl Options {
     /// Returns a builder that allows building a [Options](crate::Options) instance.    
     pub fn builder() -> OptionsBuilder {
         OptionsBuilder {
-            opts: Options {
-                include_prerelease: false,
-                loose: false,
-            },
+            opts: Options::default(),
+        }
+    }
+
+    // Enforces the limits on the version of a comparator, `input` being the range or comparator it was parsed from.
+    pub(crate) fn check_version(&self, input: &str, v: &Version) -> Result<(), Error> {
+        if v.is_any() {
+            return Ok(());
         }
+
+        let len = version_len(v.major, v.minor, v.patch, v.prerelease.as_ref());
+        self.check_length(input, len)?;
+        self.check_components(input, [v.major, v.minor, v.patch])
     }
+
+    // Rejects a version of `len` characters when it's longer than `max_length`, `input` being what it was parsed from.
+    pub(crate) fn check_length(&self, input: &str, len: usize) -> Result<(), Error> {
+        match len > self.max_length {
+            true => Err(Error::TooLong {
+                input: input.into(),
+                max_length: self.max_length,
+            }),
+            false => Ok(()),
+        }
+    }
+
+    // Rejects the `[major, minor, patch]` of a version when one of them is larger than `max_safe_integer`.
+    pub(crate) fn check_components(&self, input: &str, components: [u64; 3]) -> Result<(), Error> {
+        match components.iter().find(|&&n| n > self.max_safe_integer) {
+            Some(&number) => Err(Error::TooLarge {
+                input: input.into(),
+                number,
+                max_safe_integer: self.max_safe_integer,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Allows to configure [coerce](crate::coerce), same as the [node-semver](https://github.com/npm/node-semver#coercion) package.
+/// All options are false by default.
+/// ## Example
+/// ```
+/// # use semver_rs::{coerce, CoerceOptions};
+/// let opts = CoerceOptions { rtl: true, ..Default::default() };
+///
+/// assert_eq!(coerce("1.2.3.4", opts).unwrap().to_string(), "2.3.4");
+/// ```
+#[derive(Default, Clone, Debug, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CoerceOptions {
+    /// Search for the right-most coercible version instead of the left-most one,
+    /// e.g. `1.2.3.4` coerces to `2.3.4` instead of `1.2.3`.
+    pub rtl: bool,
+
+    /// Keep the prerelease and build metadata of the coerced version,
+    /// e.g. `1.2.3-rc.5` coerces to `1.2.3-rc.5` instead of `1.2.3`.
+    pub include_prerelease: bool,
 }
 
 /// A Builder that helps create instances of [Version](crate::Version) and [Range](crate::Range)
@@ -92,7 +179,7 @@ where
 {
     pub fn new(input: &'a str) -> Self {
         Builder {
-            _phantom: PhantomData::default(),
+            _phantom: PhantomData,
             opts: None,
             input,
         }
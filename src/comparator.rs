@@ -1,45 +1,27 @@
-use crate::builder::Options;
+use crate::builder::{IntoOptionsMaybe, Options};
 use crate::error::Error;
-use crate::expressions::{
-    COMPARATOR, COMPARATOR_LOOSE, COMP_REPLACE_CARETS, COMP_REPLACE_CARETS_LOOSE,
-    COMP_REPLACE_STARS, COMP_REPLACE_TILDES, COMP_REPLACE_TILDES_LOOSE, COMP_REPLACE_XRANGES,
-    COMP_REPLACE_XRANGES_LOOSE,
-};
 use crate::operator::Operator;
-use crate::util::{get_prerelease_prefix, increment_version, is_any_version, replacer};
+use crate::parser;
+use crate::range::Range;
 use crate::version::Version;
 
+use std::cmp::Ordering;
 use std::fmt;
-use std::{borrow::Cow, cmp::Ordering};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-/// ComparatorPair is a simple struct that can hold two comparators
-/// it knows how to format its Comparators
-#[derive(Debug)]
-pub(crate) struct ComparatorPair(pub Option<Comparator>, pub Option<Comparator>);
-
-impl fmt::Display for ComparatorPair {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match (self.0.as_ref(), self.1.as_ref()) {
-            (Some(c1), Some(c2)) => write!(f, "{} {}", c1, c2),
-            (Some(c1), None) => write!(f, "{}", c1),
-            (None, Some(c2)) => write!(f, "{}", c2),
-            (None, None) => Ok(()),
-        }
-    }
-}
-
-/// A `Comparator` is composed of an [Operator](crate::operator::Operator) and a [Version](create::version::Version).
+/// A `Comparator` is composed of an [Operator](crate::operator::Operator) and a [Version](crate::version::Version).
 /// Comparators are the building blocks of [Range](crate::range::Range)s
-#[derive(Clone, Debug)]
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Comparator {
     pub operator: Operator,
+    // kept structured, a string would lose whether it's an any or empty version
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde::structured"))]
     pub version: Version,
 
-    empty: bool,
+    pub(crate) empty: bool,
 }
 
 impl Comparator {
@@ -59,325 +41,127 @@ impl Comparator {
         }
     }
 
+    /// Parses a primitive comparator, e.g. `>=1.2.3`. Use [normalize](crate::Comparator::normalize)
+    /// for the ones that need desugaring, e.g. `^1.2.3`.
     pub fn new(comp: &str, opts: Option<Options>) -> Result<Self, Error> {
-        let cap = match opts.unwrap_or_default().loose {
-            true => COMPARATOR_LOOSE.captures(comp),
-            false => COMPARATOR.captures(comp),
-        }
-        .ok_or_else(|| Error::InvalidComparator(comp.into()))?;
-
-        let operator = match cap.get(1) {
-            Some(op) => {
-                let op = Operator::new(op.as_str());
-                if op == Operator::Eq || op == Operator::StrictEq {
-                    Operator::Empty
-                } else {
-                    op
-                }
-            }
-            None => Operator::Empty,
-        };
-
-        let version = if cap.get(2).is_none() {
-            Version::any()
-        } else {
-            let major = cap.get(3).map_or("", |v| v.as_str());
-            let minor = cap.get(4).map_or("", |v| v.as_str());
-            let patch = cap.get(5).map_or("", |v| v.as_str());
-            let prerelease = cap.get(6).map(|v| v.as_str().to_owned());
-            Version::from_parts(major.parse()?, minor.parse()?, patch.parse()?, prerelease)
-        };
-
-        Ok(Comparator {
-            operator,
-            version,
-            empty: false,
-        })
+        let opts = opts.unwrap_or_default();
+        let c = parser::comparator(comp, opts.loose)?;
+        opts.check_version(comp, &c.version)?;
+        Ok(c)
     }
 
+    /// Desugars a comparator of a range into primitive ones, e.g. `^1.2.3` into `>=1.2.3 <2.0.0-0`.
+    /// Comparators matching any version, e.g. `*` or a lone `^`, are returned empty, and invalid ones as they are.
     pub fn normalize(input: &str, loose: bool) -> String {
-        // TODO: Can we avoid using to_owned for each comparator function?
-        let mut comp = Comparator::replace_carets(input, loose).as_ref().to_owned();
-
-        comp = Comparator::replace_tildes(&comp, loose).as_ref().to_owned();
-
-        comp = Comparator::replace_xranges(&comp, loose)
-            .as_ref()
-            .to_owned();
-
-        Comparator::replace_stars(&comp).as_ref().to_owned()
+        let mut comparators = vec![];
+        match parser::desugar(input, loose, false, &mut comparators) {
+            Ok(()) => comparators
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            Err(_) => input.into(),
+        }
     }
 
-    fn replace_stars(comp: &str) -> Cow<'_, str> {
-        COMP_REPLACE_STARS.replace_all(comp, "")
+    pub fn test(&self, version: &Version) -> bool {
+        if self.version.is_any() {
+            true
+        } else if self.version.is_empty() {
+            false
+        } else {
+            Self::cmp_versions(version, &self.operator, &self.version)
+        }
     }
 
-    fn replace_xranges(comp: &str, loose: bool) -> Cow<'_, str> {
-        let repl = replacer(|args: &[String]| {
-            let version = args[0].as_str();
-            let mut op = args[1].as_str();
-            let major = args[2].as_str();
-            let minor = args[3].as_str();
-            let patch = args[4].as_str();
-
-            let is_any_major = is_any_version(major);
-            let is_any_minor = is_any_major || is_any_version(minor);
-            let is_any_patch = is_any_minor || is_any_version(patch);
-            let is_any_version = is_any_patch;
+    /// Tests whether there is at least one version that satisfies both this comparator and the `other` one.
+    /// With [Options.include_prerelease](crate::Options::include_prerelease) a prerelease satisfies
+    /// comparators of other `[major, minor, patch]` tuples too, like it does in a [Range](crate::Range).
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Comparator, Options, Error};
+    /// let c = Comparator::new("1.3.1-beta", None)?;
+    /// let other = Comparator::new(">=1.3.0-alpha", None)?;
+    /// let opts = Options::builder().include_prerelease(true).build();
+    ///
+    /// assert!(!c.intersects(&other, None));
+    /// assert!(c.intersects(&other, opts));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn intersects(&self, other: &Comparator, opts: impl IntoOptionsMaybe) -> bool {
+        let include_prerelease = opts.into().unwrap_or_default().include_prerelease;
+        self.intersects_with(other, include_prerelease)
+    }
 
-            if op == "=" && is_any_version {
-                op = ""
+    pub(crate) fn intersects_with(&self, other: &Comparator, include_prerelease: bool) -> bool {
+        if self.is_eq() {
+            if self.version.is_any() {
+                return true;
             }
-
-            let mut op = Operator::new(op);
-
-            if is_any_major {
-                if op == Operator::Lt || op == Operator::Gt {
-                    Cow::Borrowed("<0.0.0")
-                } else {
-                    Cow::Borrowed("*")
-                }
-            } else if op != Operator::Empty && is_any_version {
-                let mut parsed_minor = if !is_any_minor {
-                    minor.parse::<usize>().unwrap()
-                } else {
-                    0
-                };
-                let mut parsed_major = major.parse::<usize>().unwrap();
-                let mut parsed_patch = if is_any_patch { "0" } else { patch };
-
-                if op == Operator::Gt {
-                    op = Operator::Gte;
-                    if is_any_minor {
-                        parsed_major = increment_version(major);
-                        parsed_minor = 0;
-                        parsed_patch = "0"
-                    } else if is_any_patch {
-                        parsed_minor = increment_version(minor);
-                        parsed_patch = "0";
-                    }
-                } else if op == Operator::Lte {
-                    op = Operator::Lt;
-                    if is_any_minor {
-                        parsed_major = increment_version(major);
-                    } else {
-                        parsed_minor = increment_version(minor);
-                    }
-                }
-
-                Cow::Owned(format!(
-                    "{}{}.{}.{}",
-                    op, parsed_major, parsed_minor, parsed_patch
-                ))
-            } else if is_any_minor {
-                Cow::Owned(format!(
-                    "{}{}.0.0 {}{}.0.0",
-                    Operator::Gte,
-                    major,
-                    Operator::Lt,
-                    increment_version(major)
-                ))
-            } else if is_any_patch {
-                Cow::Owned(format!(
-                    "{}{}.{}.0 {}{}.{}.0",
-                    Operator::Gte,
-                    major,
-                    minor,
-                    Operator::Lt,
-                    major,
-                    increment_version(minor)
-                ))
-            } else {
-                // TODO: we might be able to get a reference to this
-                Cow::Owned(version.to_owned())
+            return Range::test_set(
+                std::slice::from_ref(other),
+                &self.version,
+                include_prerelease,
+            );
+        } else if other.is_eq() {
+            if other.version.is_any() {
+                return true;
             }
-        });
-
-        match loose {
-            true => COMP_REPLACE_XRANGES_LOOSE.replace_all(comp, repl),
-            false => COMP_REPLACE_XRANGES.replace_all(comp, repl),
+            return Range::test_set(
+                std::slice::from_ref(self),
+                &other.version,
+                include_prerelease,
+            );
         }
-    }
 
-    fn replace_tildes(comp: &str, loose: bool) -> Cow<'_, str> {
-        //TODO: not yet sure why this workaround is needed
-        if comp == "~" {
-            return Cow::Borrowed("*");
+        // special cases where nothing can possibly be lower
+        if self.is_below_zero(include_prerelease) || other.is_below_zero(include_prerelease) {
+            return false;
         }
 
-        let repl = replacer(|args: &[String]| {
-            let major = args[1].as_str();
-            let minor = args[2].as_str();
-            let patch = args[3].as_str();
-            let prerelease = args[4].as_str();
-
-            if is_any_version(major) {
-                Cow::Borrowed("")
-            } else if is_any_version(minor) {
-                Cow::Owned(format!(
-                    "{}{}.0.0 {}{}.0.0",
-                    Operator::Gte,
-                    major,
-                    Operator::Lt,
-                    increment_version(major)
-                ))
-            } else if is_any_version(patch) {
-                //'>=' + M + '.' + m + '.0 <' + M + '.' + (+m + 1) + '.0';
-                Cow::Owned(format!(
-                    "{}{}.{}.0 {}{}.{}.0",
-                    Operator::Gte,
-                    major,
-                    minor,
-                    Operator::Lt,
-                    major,
-                    increment_version(minor)
-                ))
-            } else if !prerelease.is_empty() {
-                Cow::Owned(format!(
-                    "{}{}.{}.{}{}{} {}{}.{}.0",
-                    Operator::Gte,
-                    major,
-                    minor,
-                    patch,
-                    get_prerelease_prefix(prerelease),
-                    prerelease,
-                    Operator::Lt,
-                    major,
-                    increment_version(minor)
-                ))
-            } else {
-                Cow::Owned(format!(
-                    "{}{}.{}.{} {}{}.{}.0",
-                    Operator::Gte,
-                    major,
-                    minor,
-                    patch,
-                    Operator::Lt,
-                    major,
-                    increment_version(minor)
-                ))
-            }
-        });
-
-        match loose {
-            true => COMP_REPLACE_TILDES_LOOSE.replace_all(comp, repl),
-            false => COMP_REPLACE_TILDES.replace_all(comp, repl),
-        }
+        let self_gt = matches!(self.operator, Operator::Gt | Operator::Gte);
+        let self_lt = matches!(self.operator, Operator::Lt | Operator::Lte);
+        let other_gt = matches!(other.operator, Operator::Gt | Operator::Gte);
+        let other_lt = matches!(other.operator, Operator::Lt | Operator::Lte);
+        let inclusive = matches!(self.operator, Operator::Gte | Operator::Lte)
+            && matches!(other.operator, Operator::Gte | Operator::Lte);
+
+        // same direction, e.g. `>1.2.0` and `>=1.3.0`
+        (self_gt && other_gt)
+            || (self_lt && other_lt)
+            // same version and both sides are inclusive, e.g. `>=1.3.0` and `<=1.3.0`
+            || (self.version == other.version && inclusive)
+            // opposite directions, e.g. `>1.0.0` and `<2.0.0`
+            || (self.version < other.version && self_gt && other_lt)
+            || (self.version > other.version && self_lt && other_gt)
     }
 
-    fn replace_carets(comp: &str, loose: bool) -> Cow<'_, str> {
-        if comp == "^" {
-            //TODO: not yet sure why this workaround is needed
-            return Cow::Borrowed("*");
-        }
-
-        let repl = replacer(|args: &[String]| {
-            let major = args[1].as_str();
-            let minor = args[2].as_str();
-            let patch = args[3].as_str();
-            let prerelease = args[4].as_str();
+    // `<0.0.0-0` can't match anything, it's what node-semver desugars such sets into, e.g. `<x`
+    pub(crate) fn is_null_set(&self) -> bool {
+        let v = &self.version;
+        self.operator == Operator::Lt
+            && !v.is_any()
+            && (v.major, v.minor, v.patch) == (0, 0, 0)
+            && v.prerelease.as_ref().is_some_and(|pre| pre.is_zero())
+    }
 
-            if is_any_version(major) {
-                Cow::Borrowed("")
-            } else if is_any_version(minor) {
-                Cow::Owned(format!(">={}.0.0 <{}.0.0", major, increment_version(major)))
-            } else if is_any_version(patch) {
-                if major == "0" {
-                    Cow::Owned(format!(
-                        ">={}.{}.0 <{}.{}.0",
-                        major,
-                        minor,
-                        major,
-                        increment_version(minor)
-                    ))
-                } else {
-                    Cow::Owned(format!(
-                        ">={}.{}.0 <{}.0.0",
-                        major,
-                        minor,
-                        increment_version(major),
-                    ))
-                }
-            } else if !prerelease.is_empty() {
-                if major == "0" {
-                    if minor == "0" {
-                        Cow::Owned(format!(
-                            ">= {}.{}.{}{}{} <{}.{}.{}",
-                            major,
-                            minor,
-                            patch,
-                            get_prerelease_prefix(prerelease),
-                            prerelease,
-                            major,
-                            minor,
-                            increment_version(patch)
-                        ))
-                    } else {
-                        Cow::Owned(format!(
-                            ">= {}.{}.{}{} <{}.{}.0",
-                            major,
-                            minor,
-                            patch,
-                            prerelease,
-                            major,
-                            increment_version(minor)
-                        ))
-                    }
-                } else {
-                    Cow::Owned(format!(
-                        ">={}.{}.{}{} <{}.0.0",
-                        major,
-                        minor,
-                        patch,
-                        prerelease,
-                        increment_version(major)
-                    ))
-                }
-            } else if major == "0" {
-                if minor == "0" {
-                    Cow::Owned(format!(
-                        ">={}.{}.{} <{}.{}.{}",
-                        major,
-                        minor,
-                        patch,
-                        major,
-                        minor,
-                        increment_version(patch),
-                    ))
-                } else {
-                    Cow::Owned(format!(
-                        "=>{}.{}.{} <{}.{}.0",
-                        major,
-                        minor,
-                        patch,
-                        major,
-                        increment_version(minor),
-                    ))
-                }
-            } else {
-                Cow::Owned(format!(
-                    ">={}.{}.{} <{}.0.0",
-                    major,
-                    minor,
-                    patch,
-                    increment_version(major),
-                ))
-            }
-        });
+    fn is_eq(&self) -> bool {
+        matches!(
+            self.operator,
+            Operator::Empty | Operator::Eq | Operator::StrictEq
+        )
+    }
 
-        match loose {
-            true => COMP_REPLACE_CARETS_LOOSE.replace_all(comp, repl),
-            false => COMP_REPLACE_CARETS.replace_all(comp, repl),
+    // `<0.0.0` matches nothing, unless prereleases are included, in which case only `<0.0.0-0` does
+    fn is_below_zero(&self, include_prerelease: bool) -> bool {
+        let v = &self.version;
+        if self.operator != Operator::Lt || v.major != 0 || v.minor != 0 || v.patch != 0 {
+            return false;
         }
-    }
 
-    pub fn test(&self, version: &Version) -> bool {
-        if self.version.is_any() {
-            true
-        } else if self.version.is_empty() {
-            false
-        } else {
-            Self::cmp_versions(version, &self.operator, &self.version)
+        match include_prerelease {
+            true => v.prerelease.as_ref().is_some_and(|pre| pre.is_zero()),
+            false => true,
         }
     }
 
@@ -417,58 +201,128 @@ mod tests {
     use super::*;
 
     #[test]
-    fn replace_carets() {
+    fn normalize_carets() {
         let v = vec![
-            ("^1.2.3", ">=1.2.3 <2.0.0"),
-            ("^1.2.0", ">=1.2.0 <2.0.0"),
-            ("^1.2", ">=1.2.0 <2.0.0"),
-            ("^2.0", ">=2.0.0 <3.0.0"),
-            ("^2", ">=2.0.0 <3.0.0"),
-            ("^", "*"),
+            ("^1.2.3", ">=1.2.3 <2.0.0-0"),
+            ("^1.2.0", ">=1.2.0 <2.0.0-0"),
+            ("^1.2", ">=1.2.0 <2.0.0-0"),
+            ("^2.0", ">=2.0.0 <3.0.0-0"),
+            ("^2", ">=2.0.0 <3.0.0-0"),
+            // a lone caret is `*`, whose star is stripped
+            ("^", ""),
         ];
         for (input, output) in v {
-            let res = Comparator::replace_carets(input, false);
-            assert_eq!(output, res);
+            let res = Comparator::normalize(input, false);
+            assert_eq!(output, res, "testing normalize: {}", input);
         }
     }
 
     #[test]
-    fn replace_tildes() {
+    fn normalize_tildes() {
         let v = vec![
-            ("~2", ">=2.0.0 <3.0.0"),
-            ("~2.0", ">=2.0.0 <2.1.0"),
-            ("~1.2", ">=1.2.0 <1.3.0"),
-            ("~1.2.3", ">=1.2.3 <1.3.0"),
-            ("~1.2.0", ">=1.2.0 <1.3.0"),
-            ("~", "*"),
+            ("~2", ">=2.0.0 <3.0.0-0"),
+            ("~2.0", ">=2.0.0 <2.1.0-0"),
+            ("~1.2", ">=1.2.0 <1.3.0-0"),
+            ("~1.2.3", ">=1.2.3 <1.3.0-0"),
+            ("~1.2.0", ">=1.2.0 <1.3.0-0"),
+            // a lone tilde is `*`, whose star is stripped
+            ("~", ""),
         ];
         for (input, output) in v {
-            let res = Comparator::replace_tildes(input, false);
-            assert_eq!(output, res);
+            let res = Comparator::normalize(input, false);
+            assert_eq!(output, res, "testing normalize: {}", input);
         }
     }
 
     #[test]
-    fn replace_xranges() {
+    fn normalize_xranges() {
         let v = vec![
             (">1", ">=2.0.0"),
             (">1.2", ">=1.3.0"),
-            ("<=0.7.x", "<0.8.0"),
-            ("<=7.x", "<8.0.0"),
+            ("<=0.7.x", "<0.8.0-0"),
+            ("<=7.x", "<8.0.0-0"),
+            ("<x", "<0.0.0-0"),
+            ("garbage", "garbage"),
         ];
-
         for (input, output) in v {
-            let res = Comparator::replace_xranges(input, false);
-            assert_eq!(output, res);
+            let res = Comparator::normalize(input, false);
+            assert_eq!(output, res, "testing normalize: {}", input);
         }
     }
 
     #[test]
-    fn replace_stars() {
-        let v = vec![("*", "")];
+    fn normalize_stars() {
+        let v = vec![("*", ""), (">=*", "")];
         for (input, output) in v {
-            let res = Comparator::replace_stars(input);
-            assert_eq!(output, res);
+            let res = Comparator::normalize(input, false);
+            assert_eq!(output, res, "testing normalize: {}", input);
+        }
+    }
+
+    #[test]
+    fn intersects() {
+        let v = vec![
+            // one is a version
+            ("1.3.0", ">=1.3.0", true, false),
+            ("1.3.0", ">1.3.0", false, false),
+            (">=1.3.0", "1.3.0", true, false),
+            (">1.3.0", "1.3.0", false, false),
+            // same direction increasing
+            (">1.3.0", ">1.2.0", true, false),
+            (">1.2.0", ">1.3.0", true, false),
+            (">=1.2.0", ">1.3.0", true, false),
+            (">1.2.0", ">=1.3.0", true, false),
+            // same direction decreasing
+            ("<1.3.0", "<1.2.0", true, false),
+            ("<1.2.0", "<1.3.0", true, false),
+            ("<=1.2.0", "<1.3.0", true, false),
+            ("<1.2.0", "<=1.3.0", true, false),
+            // different directions, same version and inclusive operator
+            (">=1.3.0", "<=1.3.0", true, false),
+            (">=v1.3.0", "<=1.3.0", true, false),
+            (">=1.3.0", ">=1.3.0", true, false),
+            ("<=1.3.0", "<=1.3.0", true, false),
+            ("<=1.3.0", "<=v1.3.0", true, false),
+            (">1.3.0", "<=1.3.0", false, false),
+            (">=1.3.0", "<1.3.0", false, false),
+            // opposite matching directions
+            (">1.0.0", "<2.0.0", true, false),
+            (">=1.0.0", "<2.0.0", true, false),
+            (">=1.0.0", "<=2.0.0", true, false),
+            (">1.0.0", "<=2.0.0", true, false),
+            ("<=2.0.0", ">1.0.0", true, false),
+            ("<=1.0.0", ">=2.0.0", false, false),
+            ("", "", true, false),
+            ("", ">1.0.0", true, false),
+            ("<=2.0.0", "", true, false),
+            ("<0.0.0", "<0.1.0", false, false),
+            ("<0.1.0", "<0.0.0", false, false),
+            ("<0.0.0-0", "<0.1.0", false, false),
+            ("<0.1.0", "<0.0.0-0", false, false),
+            ("<0.0.0-0", "<0.1.0", false, true),
+            ("<0.1.0", "<0.0.0-0", false, true),
+            ("<0.0.0", "<0.1.0", true, true),
+            // prereleases
+            ("1.3.0-beta", ">=1.3.0-alpha", true, false),
+            ("1.3.1-beta", ">=1.3.0-alpha", false, false),
+            ("1.3.1-beta", ">=1.3.0-alpha", true, true),
+        ];
+
+        for (c1, c2, expected, include_prerelease) in v {
+            let comp1 = Comparator::new(c1, None).unwrap();
+            let comp2 = Comparator::new(c2, None).unwrap();
+            let opts = Options::builder()
+                .include_prerelease(include_prerelease)
+                .build();
+            let res = comp1.intersects(&comp2, opts);
+            assert_eq!(
+                res, expected,
+                "testing intersects: {} {} include_prerelease: {}",
+                c1, c2, include_prerelease
+            );
+            if !include_prerelease {
+                assert_eq!(comp1.intersects(&comp2, None), expected);
+            }
         }
     }
 }
@@ -1,8 +1,9 @@
-use crate::builder::IntoOptionsMaybe;
+use crate::builder::{CoerceOptions, IntoOptionsMaybe};
 use crate::error::Error;
-use crate::expressions::CLEAN_VERSION;
+use crate::expressions::{CLEAN_VERSION, COERCE, COERCE_FULL};
 use crate::operator::Operator;
-use crate::range::Range;
+use crate::range::{Direction, Range};
+use crate::release_type::{IdentifierBase, ReleaseType};
 use crate::version::Version;
 
 use std::cmp::Ordering;
@@ -12,11 +13,101 @@ pub fn parse(version: &str, opts: impl IntoOptionsMaybe) -> Result<Version, Erro
     Version::new(version).with_options(opts).parse()
 }
 
-/// Cleanups a semver string making it semver complaint.
+/// Parses a string into a [Version](crate::Version) without its build metadata like node-semver's `valid`,
+/// returning `None` if it's not a valid version.
+pub fn valid(version: &str, opts: impl IntoOptionsMaybe) -> Option<Version> {
+    parse(version, opts).ok().map(Version::without_build)
+}
+
+/// Cleanups a semver string making it semver complaint, dropping its build metadata.
+/// Returns an empty string if it's not a valid version, or it's over the limits of the [Options](crate::Options).
 pub fn clean(version: &str, opts: impl IntoOptionsMaybe) -> Result<String, Error> {
     let clean_version = CLEAN_VERSION.replace_all(version.trim(), "");
 
-    Ok(parse(&clean_version, opts)?.to_string())
+    match parse(&clean_version, opts) {
+        Ok(version) => Ok(version.without_build().to_string()),
+        Err(Error::Parse(_)) | Err(Error::TooLong { .. }) | Err(Error::TooLarge { .. }) => {
+            Ok(String::new())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Increments a [Version](crate::Version) by the given [ReleaseType](crate::ReleaseType). Refer to [Version::inc](crate::Version::inc).
+pub fn inc(
+    version: &str,
+    release: ReleaseType,
+    identifier: Option<&str>,
+    identifier_base: Option<IdentifierBase>,
+    opts: impl IntoOptionsMaybe,
+) -> Result<String, Error> {
+    Ok(parse(version, opts)?
+        .inc(release, identifier, identifier_base, opts)?
+        .to_string())
+}
+
+/// Extracts a [Version](crate::Version) out of an arbitrary string, e.g. `v2` => `2.0.0` or `openssl 1.1.1k` => `1.1.1`.
+/// Missing minor and patch components are filled with `0` and anything past the patch is ignored.
+/// Returns `None` if the string contains no numbers that could be coerced.
+///
+/// Behaves like the `coerce` function of [node-semver](https://github.com/npm/node-semver#coercion).
+/// ## Example
+/// ```
+/// # use semver_rs::{coerce, CoerceOptions};
+/// let ver = coerce("release-1.2.3.4", CoerceOptions::default());
+///
+/// assert_eq!(ver.unwrap().to_string(), "1.2.3");
+/// ```
+pub fn coerce(input: &str, opts: CoerceOptions) -> Option<Version> {
+    let re = match opts.include_prerelease {
+        true => &*COERCE_FULL,
+        false => &*COERCE,
+    };
+
+    let mut locs = re.capture_locations();
+    if !opts.rtl {
+        re.captures_read(&mut locs, input)?;
+    } else {
+        // Find the right-most coercible string that does not share a terminus with a more
+        // left-ward one, e.g. `1.2.3.4` wants to coerce `2.3.4`, not `3.4` or `4`.
+        // The search is restarted right after the major of each match in order to pick up overlapping ones,
+        // and stops as soon as a match ends at the end of the input.
+        let mut next = re.capture_locations();
+        let mut found: Option<(usize, usize)> = None;
+        let mut start = 0;
+        while let Some(m) = re.captures_read_at(&mut next, input, start) {
+            if let Some((_, end)) = found {
+                if end == input.len() {
+                    break;
+                }
+            }
+
+            if found.map_or(true, |(_, end)| end != m.end()) {
+                found = Some((m.start(), m.end()));
+                locs = next.clone();
+            }
+
+            let (_, major_end) = next.get(2)?;
+            start = major_end;
+        }
+        found?;
+    }
+
+    let group = |i: usize| locs.get(i).map(|(start, end)| &input[start..end]);
+    let major = group(2)?;
+    let minor = group(3).unwrap_or("0");
+    let patch = group(4).unwrap_or("0");
+    let prerelease = match group(5) {
+        Some(prerelease) if opts.include_prerelease => format!("-{}", prerelease),
+        _ => String::new(),
+    };
+    let build = match group(6) {
+        Some(build) if opts.include_prerelease => format!("+{}", build),
+        _ => String::new(),
+    };
+
+    let version = format!("{}.{}.{}{}{}", major, minor, patch, prerelease, build);
+    parse(&version, None).ok()
 }
 
 /// Compares the ordering of [Version](crate::Version) `a` vs [Version](crate::Version) `b`.
@@ -26,6 +117,22 @@ pub fn compare(a: &str, b: &str, opts: impl IntoOptionsMaybe) -> Result<Ordering
     Ok(a.partial_cmp(&b).unwrap())
 }
 
+/// Compares the ordering of [Version](crate::Version) `a` vs [Version](crate::Version) `b`, taking their build metadata into account
+/// when they are otherwise equal. Refer to [Version::compare_build](crate::Version::compare_build).
+pub fn compare_build(a: &str, b: &str, opts: impl IntoOptionsMaybe) -> Result<Ordering, Error> {
+    let a = parse(a, opts)?;
+    let b = parse(b, opts)?;
+    Ok(a.compare_build(&b))
+}
+
+/// Returns the [ReleaseType](crate::ReleaseType) by which [Version](crate::Version) `a` and [Version](crate::Version) `b` differ,
+/// or `None` if they are equal. Refer to [Version::diff](crate::Version::diff).
+pub fn diff(a: &str, b: &str, opts: impl IntoOptionsMaybe) -> Result<Option<ReleaseType>, Error> {
+    let a = parse(a, opts)?;
+    let b = parse(b, opts)?;
+    Ok(a.diff(&b))
+}
+
 /// Compares whether [Version](crate::Version) `a` matches the semver operator against [Version](crate::Version) `b`.
 pub fn cmp(a: &str, op: Operator, b: &str, opts: impl IntoOptionsMaybe) -> Result<bool, Error> {
     let r = match op {
@@ -48,9 +155,161 @@ pub fn cmp(a: &str, op: Operator, b: &str, opts: impl IntoOptionsMaybe) -> Resul
     Ok(r)
 }
 
+/// Returns the canonical form of a [Range](crate::Range), e.g. `^1.2.3` => `>=1.2.3 <2.0.0-0`,
+/// or `None` if it's not a valid range or it's over the limits of the [Options](crate::Options).
+/// Ranges matching any version are returned as `*`.
+pub fn valid_range(range: &str, opts: impl IntoOptionsMaybe) -> Result<Option<String>, Error> {
+    match Range::new(range).with_options(opts).parse() {
+        Ok(range) => {
+            let range = range.to_string();
+            match range.is_empty() {
+                true => Ok(Some("*".into())),
+                false => Ok(Some(range)),
+            }
+        }
+        Err(Error::Parse(_))
+        | Err(Error::InvalidRange(_))
+        | Err(Error::InvalidComparator(_))
+        | Err(Error::TooLong { .. })
+        | Err(Error::TooLarge { .. }) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
 /// Checks whether [Version](crate::Version) is in a [Range](crate::Range).
 pub fn satisfies(ver: &str, range: &str, opts: impl IntoOptionsMaybe) -> Result<bool, Error> {
     let range = Range::new(range).with_options(opts).parse()?;
     let ver = Version::new(ver).with_options(opts).parse()?;
     Ok(range.test(&ver))
 }
+
+/// Returns a range matching the same subset of `versions` as the given [Range](crate::Range), made of the runs of
+/// consecutive versions satisfying it, e.g. `1.0.0 || 1.0.1 || ^1.1.0` over the versions `1.0.0`, `1.0.1`, `1.1.0`
+/// and `2.0.0` becomes `<=1.1.0`. The `range` is returned as it was given when it isn't longer than that,
+/// so it's an empty string when none of the `versions` satisfy it. Versions that fail to parse are skipped.
+///
+/// Behaves like the `simplifyRange` function of [node-semver](https://github.com/npm/node-semver#ranges).
+/// ## Example
+/// ```
+/// # use semver_rs::{simplify_range, Error};
+/// let versions = ["1.0.0", "1.0.1", "1.1.0", "2.0.0"];
+///
+/// assert_eq!(simplify_range(&versions, "1.0.0 || 1.0.1 || ^1.1.0", None)?, "<=1.1.0");
+/// assert_eq!(simplify_range(&versions, "^1.0.0", None)?, "^1.0.0");
+/// assert_eq!(simplify_range(&versions, "3.x", None)?, "");
+/// # Ok::<(), Error>(())
+/// ```
+pub fn simplify_range(
+    versions: &[&str],
+    range: &str,
+    opts: impl IntoOptionsMaybe,
+) -> Result<String, Error> {
+    let parsed = Range::new(range).with_options(opts).parse()?;
+    let mut versions = versions
+        .iter()
+        .filter_map(|s| Some((*s, Version::new(s).with_options(opts).parse().ok()?)))
+        .collect::<Vec<_>>();
+    versions.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+    // the (first, last) indices of each run of consecutive versions satisfying the range
+    let mut runs = vec![];
+    let mut first = None;
+    for (i, (_, version)) in versions.iter().enumerate() {
+        match (parsed.test(version), first) {
+            (true, None) => first = Some(i),
+            (false, Some(start)) => {
+                runs.push((start, Some(i - 1)));
+                first = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = first {
+        runs.push((start, None));
+    }
+
+    let simplified = runs
+        .iter()
+        .map(|run| match *run {
+            (min, Some(max)) if min == max => versions[min].0.to_string(),
+            (0, None) => "*".into(),
+            (min, None) => format!(">={}", versions[min].0),
+            (0, Some(max)) => format!("<={}", versions[max].0),
+            (min, Some(max)) => format!("{} - {}", versions[min].0, versions[max].0),
+        })
+        .collect::<Vec<_>>()
+        .join(" || ");
+
+    match simplified.len() < range.len() {
+        true => Ok(simplified),
+        false => Ok(range.into()),
+    }
+}
+
+/// Checks whether [Version](crate::Version) is greater than every version in a [Range](crate::Range).
+/// Refer to [Range::outside](crate::Range::outside).
+pub fn gtr(ver: &str, range: &str, opts: impl IntoOptionsMaybe) -> Result<bool, Error> {
+    outside(ver, range, Direction::High, opts)
+}
+
+/// Checks whether [Version](crate::Version) is less than every version in a [Range](crate::Range).
+/// Refer to [Range::outside](crate::Range::outside).
+pub fn ltr(ver: &str, range: &str, opts: impl IntoOptionsMaybe) -> Result<bool, Error> {
+    outside(ver, range, Direction::Low, opts)
+}
+
+/// Checks whether [Version](crate::Version) is outside of a [Range](crate::Range) in the given [Direction](crate::Direction).
+/// Refer to [Range::outside](crate::Range::outside).
+pub fn outside(
+    ver: &str,
+    range: &str,
+    direction: Direction,
+    opts: impl IntoOptionsMaybe,
+) -> Result<bool, Error> {
+    let range = Range::new(range).with_options(opts).parse()?;
+    let ver = Version::new(ver).with_options(opts).parse()?;
+    Ok(range.outside(&ver, direction))
+}
+
+/// Returns the highest of the `versions` that satisfies the [Range](crate::Range), or `None` if none of them does.
+/// Versions that fail to parse are skipped.
+pub fn max_satisfying<'a>(
+    versions: &[&'a str],
+    range: &str,
+    opts: impl IntoOptionsMaybe,
+) -> Result<Option<&'a str>, Error> {
+    let range = Range::new(range).with_options(opts).parse()?;
+    let max = parse_satisfying(versions, &range, opts).fold(None, |max, (s, v)| match max {
+        Some((max_s, max)) if max >= v => Some((max_s, max)),
+        _ => Some((s, v)),
+    });
+
+    Ok(max.map(|(s, _)| s))
+}
+
+/// Returns the lowest of the `versions` that satisfies the [Range](crate::Range), or `None` if none of them does.
+/// Versions that fail to parse are skipped.
+pub fn min_satisfying<'a>(
+    versions: &[&'a str],
+    range: &str,
+    opts: impl IntoOptionsMaybe,
+) -> Result<Option<&'a str>, Error> {
+    let range = Range::new(range).with_options(opts).parse()?;
+    let min = parse_satisfying(versions, &range, opts).fold(None, |min, (s, v)| match min {
+        Some((min_s, min)) if min <= v => Some((min_s, min)),
+        _ => Some((s, v)),
+    });
+
+    Ok(min.map(|(s, _)| s))
+}
+
+fn parse_satisfying<'a, 'r>(
+    versions: &'r [&'a str],
+    range: &'r Range,
+    opts: impl IntoOptionsMaybe + 'r,
+) -> impl Iterator<Item = (&'a str, Version)> + 'r {
+    versions
+        .iter()
+        .filter_map(move |s| Some((*s, valid(s, opts)?)))
+        .filter(move |(_, v)| range.test(v))
+}
@@ -0,0 +1,197 @@
+use crate::interval::{self, Bound, VersionInterval};
+use crate::prerelease::Prerelease;
+use crate::range::Range;
+use crate::version::Version;
+
+use std::cmp::Ordering;
+
+/// A [Range](crate::Range) precomputed for testing lots of versions against it, as returned by
+/// [Range::compile](crate::Range::compile). It matches the same versions, but each comparator set
+/// is reduced to a single pair of bounds and the `[major, minor, patch]` tuples its prereleases are allowed in,
+/// so testing a version doesn't allocate or go over every comparator.
+/// ## Example
+/// ```
+/// # use semver_rs::{Range, Version, Error};
+/// let compiled = Range::new("^1.2.3 || ~2.0.0-beta.1").parse()?.compile();
+///
+/// assert!(compiled.test(&Version::new("1.9.0").parse()?));
+/// assert!(compiled.test(&Version::new("2.0.0-beta.2").parse()?));
+/// assert!(!compiled.test(&Version::new("1.9.0-beta.2").parse()?));
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct CompiledRange {
+    sets: Vec<CompiledSet>,
+    include_prerelease: bool,
+}
+
+#[derive(Clone, Debug)]
+struct CompiledSet {
+    lower: Option<Edge>,
+    upper: Option<Edge>,
+    // the tuples of the prerelease comparators of the set, only their prereleases can match
+    prerelease_tuples: Vec<[u64; 3]>,
+}
+
+impl CompiledRange {
+    pub(crate) fn new(range: &Range) -> Self {
+        let mut sets = vec![];
+        for comparators in range.comparators.iter() {
+            let prerelease_tuples = interval::prerelease_tuples(comparators);
+
+            // a set with a `!=` comparator is split into several intervals, one with no version matches nothing
+            for interval in VersionInterval::from_set(comparators) {
+                sets.push(CompiledSet {
+                    lower: Edge::new(interval.lower),
+                    upper: Edge::new(interval.upper),
+                    prerelease_tuples: prerelease_tuples.clone(),
+                });
+            }
+        }
+
+        CompiledRange {
+            sets,
+            include_prerelease: range.include_prerelease(),
+        }
+    }
+
+    /// Tests whether a `version` is in the range, like [Range::test](crate::Range::test) does.
+    pub fn test(&self, version: &Version) -> bool {
+        let tuple = [version.major, version.minor, version.patch];
+        let check_prerelease = !self.include_prerelease && version.has_prerelease();
+
+        self.sets.iter().any(|set| {
+            set.above_lower(version)
+                && set.below_upper(version)
+                && (!check_prerelease || set.prerelease_tuples.contains(&tuple))
+        })
+    }
+}
+
+impl CompiledSet {
+    fn above_lower(&self, version: &Version) -> bool {
+        match &self.lower {
+            Some(edge) => match edge.cmp(version) {
+                Ordering::Less => true,
+                Ordering::Equal => edge.inclusive,
+                Ordering::Greater => false,
+            },
+            None => true,
+        }
+    }
+
+    fn below_upper(&self, version: &Version) -> bool {
+        match &self.upper {
+            Some(edge) => match edge.cmp(version) {
+                Ordering::Less => false,
+                Ordering::Equal => edge.inclusive,
+                Ordering::Greater => true,
+            },
+            None => true,
+        }
+    }
+}
+
+// A bounded end of a set, split up so that most versions are compared by their tuple alone.
+#[derive(Clone, Debug)]
+struct Edge {
+    tuple: [u64; 3],
+    prerelease: Option<Prerelease>,
+    inclusive: bool,
+}
+
+impl Edge {
+    fn new(bound: Bound) -> Option<Self> {
+        let (v, inclusive) = match bound {
+            Bound::Included(v) => (v, true),
+            Bound::Excluded(v) => (v, false),
+            Bound::Unbounded => return None,
+        };
+
+        Some(Edge {
+            tuple: [v.major, v.minor, v.patch],
+            prerelease: v.prerelease,
+            inclusive,
+        })
+    }
+
+    // Orders the edge relative to a `version`, the same way `Version` does.
+    fn cmp(&self, version: &Version) -> Ordering {
+        match self
+            .tuple
+            .cmp(&[version.major, version.minor, version.patch])
+        {
+            Ordering::Equal => match (&self.prerelease, &version.prerelease) {
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+                (Some(pre1), Some(pre2)) => pre1.cmp(pre2),
+            },
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builder::Options;
+
+    #[test]
+    fn same_as_range() {
+        let ranges = [
+            "",
+            "*",
+            "^1.2.3",
+            "~1.2.3-beta.2",
+            "1.2.3-alpha - 2.0.0-rc.1",
+            ">=1.2.3 <2.0.0 || 3.x",
+            ">1.2.3-beta <1.2.3 || >=2.0.0-0",
+            "1.2.3-beta || 1.2.3",
+            "<0.0.0-0",
+            "<0.0.0",
+            ">1.0.0 <1.0.0",
+            "* || ^1.2.3-beta",
+            "=1.2.3-beta.1",
+            "<=1.2.3-beta.1 >=1.2.3-alpha",
+        ];
+        let versions = [
+            "0.0.0",
+            "0.0.0-0",
+            "1.0.0",
+            "1.2.3-alpha",
+            "1.2.3-beta",
+            "1.2.3-beta.1",
+            "1.2.3-beta.2",
+            "1.2.3",
+            "1.2.4-beta",
+            "1.9.9",
+            "2.0.0-rc.1",
+            "2.0.0-rc.2",
+            "2.0.0",
+            "2.0.1-beta",
+            "3.4.5",
+        ];
+
+        for include_prerelease in [false, true].iter() {
+            let opts = Options::builder()
+                .include_prerelease(*include_prerelease)
+                .build();
+            for range in ranges.iter() {
+                let r = Range::new(range).with_options(opts).parse().unwrap();
+                let compiled = r.compile();
+                for ver in versions.iter() {
+                    let v = Version::new(ver).parse().unwrap();
+                    assert_eq!(
+                        compiled.test(&v),
+                        r.test(&v),
+                        "testing compiled: {} {} {}",
+                        range,
+                        ver,
+                        include_prerelease
+                    );
+                }
+            }
+        }
+    }
+}
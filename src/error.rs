@@ -1,12 +1,95 @@
+use std::fmt;
+use std::ops::Range;
+
 /// An error returned during parsing of [Versions](crate::Version) or [Ranges](crate::Range).
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error(transparent)]
     ParseInt(#[from] std::num::ParseIntError),
 
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+
     #[error("invalid comparator: {0}")]
     InvalidComparator(String),
 
     #[error("invalid range: {0}")]
     InvalidRange(String),
+
+    #[error("invalid increment: {0}")]
+    InvalidIncrement(String),
+
+    #[error("invalid prerelease: {0}")]
+    InvalidPrerelease(String),
+
+    /// A version longer than [Options.max_length](crate::Options::max_length).
+    #[error("version longer than {max_length} characters: {input}")]
+    TooLong { input: String, max_length: usize },
+
+    /// A version number larger than [Options.max_safe_integer](crate::Options::max_safe_integer).
+    #[error("version number {number} larger than {max_safe_integer}: {input}")]
+    TooLarge {
+        input: String,
+        number: u64,
+        max_safe_integer: u64,
+    },
+}
+
+/// Describes where and why a [Version](crate::Version) or a [Range](crate::Range) failed to parse.
+/// ## Example
+/// ```
+/// # use semver_rs::{Error, ParseErrorKind, Version};
+/// match Version::new("1.02.3").parse() {
+///     Err(Error::Parse(err)) => {
+///         assert_eq!(err.kind, ParseErrorKind::LeadingZero);
+///         assert_eq!(err.span, 2..4);
+///     }
+///     res => panic!("unexpected {:?}", res),
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("{kind} at position {} in {input:?}", .span.start)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// The whole input that was being parsed.
+    pub input: String,
+    /// The byte range of the offending part of the `input`, empty when something is missing at its end.
+    pub span: Range<usize>,
+    /// A suggestion on how to fix the input.
+    pub hint: Option<String>,
+}
+
+/// The reason a [ParseError](crate::ParseError) was raised.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+pub enum ParseErrorKind {
+    /// A numeric component or identifier starts with a `0`, e.g. `01.2.3`.
+    LeadingZero,
+    /// A prerelease or build identifier is empty, e.g. `1.2.3-beta..1`.
+    EmptyIdentifier,
+    /// An operator is not one of `<`, `<=`, `>`, `>=`, `=`, `~`, `~>` or `^`, e.g. `=>1.2.3`.
+    InvalidOperator,
+    /// A hyphen range is missing one of its sides, e.g. `1.2.3 -`.
+    DanglingHyphen,
+    /// A numeric component doesn't fit in the integer it's stored in.
+    Overflow,
+    /// A character that is not allowed at its position, e.g. `1.2.3_`.
+    IllegalCharacter,
+    /// The input ends before a required part, e.g. `1.2`.
+    MissingComponent,
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            ParseErrorKind::LeadingZero => "leading zero",
+            ParseErrorKind::EmptyIdentifier => "empty identifier",
+            ParseErrorKind::InvalidOperator => "invalid operator",
+            ParseErrorKind::DanglingHyphen => "dangling hyphen",
+            ParseErrorKind::Overflow => "number too large",
+            ParseErrorKind::IllegalCharacter => "illegal character",
+            ParseErrorKind::MissingComponent => "missing component",
+        };
+
+        write!(f, "{}", s)
+    }
 }
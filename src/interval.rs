@@ -0,0 +1,535 @@
+use crate::comparator::Comparator;
+use crate::operator::Operator;
+use crate::version::Version;
+
+use std::cmp::Ordering;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// One end of a [VersionInterval](crate::VersionInterval).
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Bound {
+    /// The version is part of the interval, e.g. `>=1.2.3`.
+    Included(Version),
+    /// The version is not part of the interval, e.g. `>1.2.3`.
+    Excluded(Version),
+    /// There's no limit on this side of the interval.
+    Unbounded,
+}
+
+impl Bound {
+    /// Returns the version of the bound, or `None` if it's unbounded.
+    pub fn version(&self) -> Option<&Version> {
+        match self {
+            Bound::Unbounded => None,
+            Bound::Included(v) | Bound::Excluded(v) => Some(v),
+        }
+    }
+}
+
+/// A contiguous span of versions between a `lower` and an `upper` [Bound](crate::Bound),
+/// as returned by [Range::to_intervals](crate::Range::to_intervals).
+#[derive(PartialEq, Eq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct VersionInterval {
+    pub lower: Bound,
+    pub upper: Bound,
+
+    /// Whether any prerelease version in the interval is allowed. Otherwise only the prereleases sharing
+    /// the `[major, minor, patch]` tuple of a prerelease bound are, e.g. `1.2.3-beta.2` for `>=1.2.3-beta.1`.
+    /// Refer to [Options.include_prerelease](crate::Options::include_prerelease).
+    pub include_prerelease: bool,
+}
+
+impl VersionInterval {
+    fn new(lower: Bound, upper: Bound) -> Self {
+        VersionInterval {
+            lower,
+            upper,
+            include_prerelease: false,
+        }
+    }
+
+    /// Tests whether a `version` is between the bounds of this interval, ignoring the prerelease rules of ranges.
+    pub fn contains(&self, version: &Version) -> bool {
+        let above_lower = match &self.lower {
+            Bound::Included(v) => version >= v,
+            Bound::Excluded(v) => version > v,
+            Bound::Unbounded => true,
+        };
+        let below_upper = match &self.upper {
+            Bound::Included(v) => version <= v,
+            Bound::Excluded(v) => version < v,
+            Bound::Unbounded => true,
+        };
+
+        above_lower && below_upper
+    }
+
+    fn full() -> Self {
+        VersionInterval::new(Bound::Unbounded, Bound::Unbounded)
+    }
+
+    /// Converts a single comparator into the intervals it matches, ignoring the prerelease rules of ranges.
+    pub(crate) fn from_comparator(c: &Comparator) -> Vec<VersionInterval> {
+        if c.version.is_any() {
+            return vec![VersionInterval::full()];
+        } else if c.version.is_empty() {
+            return vec![];
+        }
+
+        let v = c.version.clone().without_build();
+        let interval = match c.operator {
+            Operator::Gt => VersionInterval::new(Bound::Excluded(v), Bound::Unbounded),
+            Operator::Gte => VersionInterval::new(Bound::Included(v), Bound::Unbounded),
+            Operator::Lt => VersionInterval::new(Bound::Unbounded, Bound::Excluded(v)),
+            Operator::Lte => VersionInterval::new(Bound::Unbounded, Bound::Included(v)),
+            Operator::Empty | Operator::Eq | Operator::StrictEq => {
+                VersionInterval::new(Bound::Included(v.clone()), Bound::Included(v))
+            }
+            Operator::Ne | Operator::StrictNe => {
+                return normalize(vec![
+                    VersionInterval::new(Bound::Unbounded, Bound::Excluded(v.clone())),
+                    VersionInterval::new(Bound::Excluded(v), Bound::Unbounded),
+                ])
+            }
+        };
+
+        normalize(vec![interval])
+    }
+
+    /// Converts a comparator set, which matches the versions all of its comparators match, into intervals.
+    pub(crate) fn from_set(comparators: &[Comparator]) -> Vec<VersionInterval> {
+        comparators
+            .iter()
+            .fold(vec![VersionInterval::full()], |acc, c| {
+                intersect(&acc, &VersionInterval::from_comparator(c))
+            })
+    }
+
+    /// Converts the interval back into the comparators of a single comparator set.
+    pub(crate) fn to_comparators(&self) -> Vec<Comparator> {
+        if let (Bound::Included(l), Bound::Included(u)) = (&self.lower, &self.upper) {
+            if l == u {
+                return vec![Comparator::from_parts(Operator::Empty, l.clone())];
+            }
+        }
+
+        let lower = match &self.lower {
+            Bound::Unbounded => None,
+            Bound::Included(v) => Some(Comparator::from_parts(Operator::Gte, v.clone())),
+            Bound::Excluded(v) => Some(Comparator::from_parts(Operator::Gt, v.clone())),
+        };
+        let upper = match &self.upper {
+            Bound::Unbounded => None,
+            Bound::Included(v) => Some(Comparator::from_parts(Operator::Lte, v.clone())),
+            Bound::Excluded(v) => Some(Comparator::from_parts(Operator::Lt, v.clone())),
+        };
+
+        lower.into_iter().chain(upper).collect()
+    }
+
+    fn is_empty(&self) -> bool {
+        match (&self.lower, &self.upper) {
+            (_, Bound::Excluded(u)) if *u == min_version() => true,
+            // nothing is between a release and the first prerelease of the next one, e.g. `>1.2.3 <1.2.4-0`
+            (Bound::Excluded(l), Bound::Excluded(u)) if !l.has_prerelease() => {
+                l.next_release()
+                    .map(|next| first_prerelease(&next))
+                    .as_ref()
+                    >= Some(u)
+                    || l >= u
+            }
+            (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+            (Bound::Included(l), Bound::Included(u)) => l > u,
+            (l, u) => l.version() >= u.version(),
+        }
+    }
+
+    fn intersect(&self, other: &VersionInterval) -> VersionInterval {
+        let lower = match cmp_lower(&self.lower, &other.lower) {
+            Ordering::Less => other.lower.clone(),
+            _ => self.lower.clone(),
+        };
+        let upper = match cmp_upper(&self.upper, &other.upper) {
+            Ordering::Greater => other.upper.clone(),
+            _ => self.upper.clone(),
+        };
+
+        VersionInterval {
+            lower,
+            upper,
+            include_prerelease: self.include_prerelease && other.include_prerelease,
+        }
+    }
+}
+
+// the lowest possible version, nothing can be below it
+fn min_version() -> Version {
+    Version::from_parts(0, 0, 0, Some("0".into()), None)
+}
+
+// the lowest prerelease of the `[major, minor, patch]` tuple of `version`, e.g. `1.2.3-0`
+fn first_prerelease(version: &Version) -> Version {
+    Version::from_parts(
+        version.major,
+        version.minor,
+        version.patch,
+        Some("0".into()),
+        None,
+    )
+}
+
+// orders lower bounds by the first version they let in
+fn cmp_lower(a: &Bound, b: &Bound) -> Ordering {
+    match (a, b) {
+        (Bound::Unbounded, Bound::Unbounded) => Ordering::Equal,
+        (Bound::Unbounded, _) => Ordering::Less,
+        (_, Bound::Unbounded) => Ordering::Greater,
+        (Bound::Included(a), Bound::Excluded(b)) if a == b => Ordering::Less,
+        (Bound::Excluded(a), Bound::Included(b)) if a == b => Ordering::Greater,
+        (a, b) => a.version().cmp(&b.version()),
+    }
+}
+
+// orders upper bounds by the last version they let in
+fn cmp_upper(a: &Bound, b: &Bound) -> Ordering {
+    match (a, b) {
+        (Bound::Unbounded, Bound::Unbounded) => Ordering::Equal,
+        (Bound::Unbounded, _) => Ordering::Greater,
+        (_, Bound::Unbounded) => Ordering::Less,
+        (Bound::Included(a), Bound::Excluded(b)) if a == b => Ordering::Greater,
+        (Bound::Excluded(a), Bound::Included(b)) if a == b => Ordering::Less,
+        (a, b) => a.version().cmp(&b.version()),
+    }
+}
+
+// whether an interval ending at `upper` touches or overlaps one starting at `lower`
+fn is_connected(upper: &Bound, lower: &Bound) -> bool {
+    match (upper, lower) {
+        (Bound::Unbounded, _) | (_, Bound::Unbounded) => true,
+        (Bound::Excluded(u), Bound::Excluded(l)) => u > l,
+        (u, l) => u.version() >= l.version(),
+    }
+}
+
+/// Sorts the intervals, drops the empty ones and merges the ones that overlap or touch.
+pub(crate) fn normalize(mut intervals: Vec<VersionInterval>) -> Vec<VersionInterval> {
+    for interval in intervals.iter_mut() {
+        if interval.lower == Bound::Included(min_version()) {
+            interval.lower = Bound::Unbounded;
+        }
+    }
+    intervals.retain(|i| !i.is_empty());
+    intervals.sort_by(|a, b| cmp_lower(&a.lower, &b.lower));
+
+    let mut merged: Vec<VersionInterval> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if is_connected(&last.upper, &interval.lower) => {
+                if cmp_upper(&interval.upper, &last.upper) == Ordering::Greater {
+                    last.upper = interval.upper;
+                }
+                last.include_prerelease |= interval.include_prerelease;
+            }
+            _ => merged.push(interval),
+        }
+    }
+
+    merged
+}
+
+/// The versions in both `a` and `b`.
+pub(crate) fn intersect(a: &[VersionInterval], b: &[VersionInterval]) -> Vec<VersionInterval> {
+    let intervals = a
+        .iter()
+        .flat_map(|a| b.iter().map(move |b| a.intersect(b)))
+        .collect();
+
+    normalize(intervals)
+}
+
+/// The versions in either `a` or `b`.
+pub(crate) fn union(a: &[VersionInterval], b: &[VersionInterval]) -> Vec<VersionInterval> {
+    normalize(a.iter().chain(b).cloned().collect())
+}
+
+/// The versions not in the normalized `intervals`.
+pub(crate) fn complement(intervals: &[VersionInterval]) -> Vec<VersionInterval> {
+    let mut gaps = vec![];
+    let mut lower = Bound::Unbounded;
+    let mut lower_open = true;
+    for interval in intervals {
+        let upper = match &interval.lower {
+            Bound::Unbounded => None,
+            Bound::Included(v) => Some(Bound::Excluded(v.clone())),
+            Bound::Excluded(v) => Some(Bound::Included(v.clone())),
+        };
+        if let Some(upper) = upper {
+            if lower_open {
+                gaps.push(VersionInterval::new(lower.clone(), upper));
+            }
+        }
+
+        lower = match &interval.upper {
+            Bound::Unbounded => {
+                lower_open = false;
+                Bound::Unbounded
+            }
+            Bound::Included(v) => Bound::Excluded(v.clone()),
+            Bound::Excluded(v) => Bound::Included(v.clone()),
+        };
+    }
+    if lower_open {
+        gaps.push(VersionInterval::new(lower, Bound::Unbounded));
+    }
+
+    normalize(gaps)
+}
+
+/// Keeps only what the intervals say about releases, e.g. `<1.2.4-beta` and `<=1.2.3` both become `<1.2.4`.
+pub(crate) fn releases(intervals: &[VersionInterval]) -> Vec<VersionInterval> {
+    let release = |v: &Version| Version::from_parts(v.major, v.minor, v.patch, None, None);
+
+    let intervals = intervals
+        .iter()
+        .map(|i| {
+            let lower = match &i.lower {
+                Bound::Included(v) | Bound::Excluded(v) if v.has_prerelease() => {
+                    Bound::Included(release(v))
+                }
+                Bound::Excluded(v) => v
+                    .next_release()
+                    .map_or(Bound::Excluded(release(v)), Bound::Included),
+                bound => bound.clone(),
+            };
+            let upper = match &i.upper {
+                Bound::Included(v) | Bound::Excluded(v) if v.has_prerelease() => {
+                    Bound::Excluded(release(v))
+                }
+                Bound::Included(v) => v.next_release().map_or(Bound::Unbounded, Bound::Excluded),
+                bound => bound.clone(),
+            };
+
+            VersionInterval {
+                lower: match lower {
+                    Bound::Included(v) if v == Version::from_parts(0, 0, 0, None, None) => {
+                        Bound::Unbounded
+                    }
+                    lower => lower,
+                },
+                upper,
+                include_prerelease: i.include_prerelease,
+            }
+        })
+        .collect();
+
+    normalize(intervals)
+}
+
+/// The interval of all the prereleases of the `[major, minor, patch]` tuple of `version`.
+pub(crate) fn prereleases(version: &Version) -> VersionInterval {
+    let (major, minor, patch) = (version.major, version.minor, version.patch);
+
+    VersionInterval::new(
+        Bound::Included(first_prerelease(version)),
+        Bound::Excluded(Version::from_parts(major, minor, patch, None, None)),
+    )
+}
+
+/// The `[major, minor, patch]` tuples of the prerelease comparators of a set,
+/// unless prereleases are included it can only match the prereleases of those.
+/// A null set, e.g. `<0.0.0-0`, doesn't match any.
+pub(crate) fn prerelease_tuples(comparators: &[Comparator]) -> Vec<[u64; 3]> {
+    let mut tuples: Vec<_> = comparators
+        .iter()
+        .filter(|c| !c.version.is_any() && c.version.has_prerelease() && !c.is_null_set())
+        .map(|c| [c.version.major, c.version.minor, c.version.patch])
+        .collect();
+    tuples.sort_unstable();
+    tuples.dedup();
+
+    tuples
+}
+
+fn tuple_prereleases(tuple: &[u64; 3]) -> VersionInterval {
+    prereleases(&Version::from_parts(
+        tuple[0], tuple[1], tuple[2], None, None,
+    ))
+}
+
+/// Converts a comparator set into the intervals of the versions it matches when prereleases aren't included,
+/// out of the releases and the prereleases of the `tuples`. Refer to [prerelease_sets].
+pub(crate) fn from_set_matching(
+    comparators: &[Comparator],
+    tuples: &[[u64; 3]],
+) -> Vec<VersionInterval> {
+    let allowed = prerelease_tuples(comparators);
+    let left_out = tuples
+        .iter()
+        .filter(|t| !allowed.contains(t))
+        .map(tuple_prereleases)
+        .collect();
+
+    intersect(
+        &VersionInterval::from_set(comparators),
+        &complement(&normalize(left_out)),
+    )
+}
+
+/// Splits and joins normalized intervals of releases and prereleases of the `tuples`, as returned by
+/// [from_set_matching], so that as comparator sets they match the same versions when prereleases aren't included.
+/// A set only matches the prereleases of the tuples of its bounds, e.g. `>=1.0.0 <3.0.0-0` doesn't match `2.0.0-beta`,
+/// so `>=1.0.0 <2.0.0-0` and `>=2.0.0 <3.0.0-0` are joined into it, and `>=1.0.0 <3.0.0` including the prereleases
+/// of `2.0.0` is split into `>=1.0.0 <2.0.0-0` and `>=2.0.0-0 <3.0.0`.
+pub(crate) fn prerelease_sets(
+    intervals: Vec<VersionInterval>,
+    tuples: &[[u64; 3]],
+) -> Vec<VersionInterval> {
+    let matches_tuple = |bound: &Bound, tuple: &[u64; 3]| match bound.version() {
+        Some(v) => v.has_prerelease() && [v.major, v.minor, v.patch] == *tuple,
+        None => false,
+    };
+
+    let mut split = vec![];
+    for mut interval in intervals {
+        for tuple in tuples {
+            let unlocked =
+                matches_tuple(&interval.lower, tuple) || matches_tuple(&interval.upper, tuple);
+            if !unlocked && !interval.intersect(&tuple_prereleases(tuple)).is_empty() {
+                let first =
+                    Version::from_parts(tuple[0], tuple[1], tuple[2], Some("0".into()), None);
+                split.push(VersionInterval {
+                    lower: interval.lower,
+                    upper: Bound::Excluded(first.clone()),
+                    include_prerelease: interval.include_prerelease,
+                });
+                interval.lower = Bound::Included(first);
+            }
+        }
+        split.push(interval);
+    }
+    split.retain(|i| !i.is_empty());
+
+    let mut joined: Vec<VersionInterval> = Vec::with_capacity(split.len());
+    for interval in split {
+        match joined.last_mut() {
+            Some(last) if only_prereleases_between(&last.upper, &interval.lower) => {
+                last.upper = interval.upper;
+            }
+            _ => joined.push(interval),
+        }
+    }
+
+    joined
+}
+
+// whether an interval ending at `upper` is only separated from one starting at `lower` by the prereleases
+// of a single tuple, e.g. `<2.0.0-0` and `>=2.0.0`
+fn only_prereleases_between(upper: &Bound, lower: &Bound) -> bool {
+    match (upper, lower) {
+        (Bound::Excluded(u), Bound::Included(l)) => {
+            !l.has_prerelease() && *u == first_prerelease(l)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn intervals(range: &str) -> Vec<VersionInterval> {
+        crate::Range::new(range).parse().unwrap().to_intervals()
+    }
+
+    fn render(intervals: &[VersionInterval]) -> String {
+        intervals
+            .iter()
+            .map(|i| {
+                i.to_comparators()
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join("||")
+    }
+
+    #[test]
+    fn normalize() {
+        let v = vec![
+            (">=1.0.0 <1.2.0 || >=1.1.0 <2.0.0", ">=1.0.0 <2.0.0"),
+            ("<1.2.3 || >=1.2.3 <2.0.0", "<2.0.0"),
+            ("<1.2.3 || >1.2.3", "<1.2.3||>1.2.3"),
+            ("<=1.2.3 || >1.2.3", ""),
+            ("1.2.3 || 1.2.3", "1.2.3"),
+            (">=2.0.0-0 || ^1.0.0", ">=1.0.0"),
+            (">2.0.0 <1.0.0 || 3.0.0", "3.0.0"),
+            (">=1.2.3 <=1.2.3", "1.2.3"),
+            (">=0.0.0-0 <1.0.0", "<1.0.0"),
+        ];
+
+        for (range, expected) in v {
+            assert_eq!(
+                render(&intervals(range)),
+                expected,
+                "testing normalize: {}",
+                range
+            );
+        }
+    }
+
+    #[test]
+    fn complement() {
+        let v = vec![
+            ("^1.2.3", "<1.2.3||>=2.0.0-0"),
+            ("1.2.3", "<1.2.3||>1.2.3"),
+            ("<1.0.0 || >=2.0.0", ">=1.0.0 <2.0.0"),
+            ("<=1.0.0", ">1.0.0"),
+            ("*", "<0.0.0-0"),
+        ];
+
+        for (range, expected) in v {
+            let res = super::complement(&intervals(range));
+            let res = match res.is_empty() {
+                true => "<0.0.0-0".to_string(),
+                false => render(&res),
+            };
+            assert_eq!(res, expected, "testing complement: {}", range);
+        }
+    }
+
+    #[test]
+    fn contains() {
+        let opts = crate::Options::builder().include_prerelease(true).build();
+        let range = crate::Range::new("^1.2.3 || 3.0.0")
+            .with_options(opts)
+            .parse()
+            .unwrap();
+        let intervals = range.to_intervals();
+        assert!(intervals.iter().all(|i| i.include_prerelease));
+
+        // version, index of the interval containing it
+        let v = vec![
+            ("1.2.2", None),
+            ("1.2.3-beta", None),
+            ("1.2.3", Some(0)),
+            ("1.9.9", Some(0)),
+            ("1.9.9-beta", Some(0)),
+            ("2.0.0-beta", None),
+            ("2.0.0", None),
+            ("3.0.0", Some(1)),
+            ("3.0.0+build", Some(1)),
+        ];
+
+        for (ver, expected) in v {
+            let ver = Version::new(ver).parse().unwrap();
+            let res = intervals.iter().position(|i| i.contains(&ver));
+            assert_eq!(res, expected, "testing contains: {}", ver);
+        }
+    }
+}
@@ -58,11 +58,15 @@
 //!
 //! #### Serialisation with Serde
 //! ```
+//! # #[cfg(feature = "serde")] {
 //! use semver_rs::{Range, Options};
 //!
+//! let range: Range = serde_json::from_str(r#""^1.2.3""#)?;
+//! assert_eq!(serde_json::to_string(&range)?, r#"">=1.2.3 <2.0.0-0""#);
+//!
 //! let opts = Options::builder().loose(true).include_prerelease(true).build();
-//! let range = Range::new(">=1.2.3").with_options(opts).parse()?;
 //! let _ = serde_json::to_string(&opts)?;
+//! # }
 //!
 //! # Ok::<(), Box<dyn std::error::Error + 'static>>(())
 //! ```
@@ -70,24 +74,41 @@
 mod builder;
 mod comparator;
 mod compare_fns;
+mod compiled_range;
 mod error;
 mod expressions;
+mod interval;
+mod lint;
 mod operator;
+mod parser;
+mod prerelease;
 mod range;
+mod release_type;
+#[cfg(feature = "serde")]
+pub mod serde;
 mod util;
 mod version;
+mod version_ref;
 
-pub use builder::{Builder, Options, OptionsBuilder, Parseable};
+pub use builder::{Builder, CoerceOptions, Options, OptionsBuilder, Parseable};
+pub use comparator::Comparator;
 pub use compare_fns::*;
-pub use error::Error;
+pub use compiled_range::CompiledRange;
+pub use error::{Error, ParseError, ParseErrorKind};
+pub use interval::{Bound, VersionInterval};
+pub use lint::{Lint, LintKind};
 pub use operator::Operator;
-pub use range::Range;
-pub use version::Version;
+pub use prerelease::{Identifier, Prerelease};
+pub use range::{Direction, Range};
+pub use release_type::{IdentifierBase, ReleaseType};
+pub use version::{AsVersion, Version};
+pub use version_ref::VersionRef;
 
 #[cfg(test)]
 mod tests {
-    use crate::builder::Options;
+    use crate::builder::{CoerceOptions, Options};
     use crate::operator::Operator;
+    use crate::release_type::{IdentifierBase, ReleaseType};
     use std::cmp::Ordering;
 
     #[test]
@@ -97,6 +118,8 @@ mod tests {
             (" 1.2.3  ", "1.2.3"),
             (" 1.2.3-4  ", "1.2.3-4"),
             (" 1.2.3-pre  ", "1.2.3-pre"),
+            ("1.2.3+build", "1.2.3"),
+            (" v1.2.3-beta.1+build.5 ", "1.2.3-beta.1"),
             ("  =v1.2.3   ", "1.2.3"),
             ("v1.2.3", "1.2.3"),
             ("  v1.2.3 ", "1.2.3"),
@@ -111,6 +134,11 @@ mod tests {
             let res = super::clean(input, None).expect(input);
             assert_eq!(res, output, "testing: clean {} => {}", input, output);
         }
+
+        // versions over node-semver's limits aren't valid either
+        let long = format!("1.2.3-{}", "a".repeat(251));
+        assert_eq!(super::clean(&long, None).unwrap(), "");
+        assert_eq!(super::clean("9007199254740992.0.0", None).unwrap(), "");
     }
 
     #[test]
@@ -453,7 +481,6 @@ mod tests {
     fn unlocked_prerelease_range() {
         let v = vec![
             ("*", "1.0.0-rc1"),
-            ("^1.0.0", "2.0.0-rc1"),
             ("^1.0.0-0", "1.0.1-rc1"),
             ("^1.0.0-rc2", "1.0.1-rc1"),
             ("^1.0.0", "1.0.1-rc1"),
@@ -469,7 +496,11 @@ mod tests {
 
     #[test]
     fn negative_unlocked_prerelease_range() {
-        let v = vec![("^1.0.0", "1.0.0-rc1"), ("^1.2.3-rc2", "2.0.0")];
+        let v = vec![
+            ("^1.0.0", "1.0.0-rc1"),
+            ("^1.0.0", "2.0.0-rc1"),
+            ("^1.2.3-rc2", "2.0.0"),
+        ];
 
         for (range, ver) in v {
             let opts = Options::builder().include_prerelease(true).build();
@@ -481,4 +512,1363 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn inc() {
+        use IdentifierBase::*;
+        use ReleaseType::*;
+
+        // version, release, identifier, identifier base, result, loose
+        let v = vec![
+            ("1.2.3", Major, None, None, Some("2.0.0"), false),
+            ("1.2.3", Minor, None, None, Some("1.3.0"), false),
+            ("1.2.3", Patch, None, None, Some("1.2.4"), false),
+            ("1.2.3tag", Major, None, None, Some("2.0.0"), true),
+            ("1.2.3-tag", Major, None, None, Some("2.0.0"), false),
+            ("1.2.0-0", Patch, None, None, Some("1.2.0"), false),
+            ("1.2.3-4", Major, None, None, Some("2.0.0"), false),
+            ("1.2.3-4", Minor, None, None, Some("1.3.0"), false),
+            ("1.2.3-4", Patch, None, None, Some("1.2.3"), false),
+            (
+                "1.2.3-alpha.0.beta",
+                Major,
+                None,
+                None,
+                Some("2.0.0"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.0.beta",
+                Minor,
+                None,
+                None,
+                Some("1.3.0"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.0.beta",
+                Patch,
+                None,
+                None,
+                Some("1.2.3"),
+                false,
+            ),
+            ("1.2.4", Prerelease, None, None, Some("1.2.5-0"), false),
+            ("1.2.3-0", Prerelease, None, None, Some("1.2.3-1"), false),
+            (
+                "1.2.3-alpha.0",
+                Prerelease,
+                None,
+                None,
+                Some("1.2.3-alpha.1"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.1",
+                Prerelease,
+                None,
+                None,
+                Some("1.2.3-alpha.2"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.0.beta",
+                Prerelease,
+                None,
+                None,
+                Some("1.2.3-alpha.1.beta"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.10.0.beta",
+                Prerelease,
+                None,
+                None,
+                Some("1.2.3-alpha.10.1.beta"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.10.beta.0",
+                Prerelease,
+                None,
+                None,
+                Some("1.2.3-alpha.10.beta.1"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.9.beta",
+                Prerelease,
+                None,
+                None,
+                Some("1.2.3-alpha.10.beta"),
+                false,
+            ),
+            ("1.2.0", Prepatch, None, None, Some("1.2.1-0"), false),
+            ("1.2.0-1", Prepatch, None, None, Some("1.2.1-0"), false),
+            ("1.2.0", Preminor, None, None, Some("1.3.0-0"), false),
+            ("1.2.3-1", Preminor, None, None, Some("1.3.0-0"), false),
+            ("1.2.0", Premajor, None, None, Some("2.0.0-0"), false),
+            ("1.2.3-1", Premajor, None, None, Some("2.0.0-0"), false),
+            ("1.2.0-1", Minor, None, None, Some("1.2.0"), false),
+            ("1.0.0-1", Major, None, None, Some("1.0.0"), false),
+            ("1.2.3+build", Patch, None, None, Some("1.2.4"), false),
+            ("1.2.3", Major, Some("dev"), None, Some("2.0.0"), false),
+            (
+                "1.2.4",
+                Prerelease,
+                Some("dev"),
+                None,
+                Some("1.2.5-dev.0"),
+                false,
+            ),
+            (
+                "1.2.3-0",
+                Prerelease,
+                Some("dev"),
+                None,
+                Some("1.2.3-dev.0"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.0",
+                Prerelease,
+                Some("dev"),
+                None,
+                Some("1.2.3-dev.0"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.0",
+                Prerelease,
+                Some("alpha"),
+                None,
+                Some("1.2.3-alpha.1"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.0.beta",
+                Prerelease,
+                Some("alpha"),
+                None,
+                Some("1.2.3-alpha.1.beta"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.10.beta.0",
+                Prerelease,
+                Some("dev"),
+                None,
+                Some("1.2.3-dev.0"),
+                false,
+            ),
+            (
+                "1.2.3-alpha.9.beta",
+                Prerelease,
+                Some("alpha"),
+                None,
+                Some("1.2.3-alpha.10.beta"),
+                false,
+            ),
+            (
+                "1.2.0",
+                Prepatch,
+                Some("dev"),
+                None,
+                Some("1.2.1-dev.0"),
+                false,
+            ),
+            (
+                "1.2.0",
+                Preminor,
+                Some("dev"),
+                None,
+                Some("1.3.0-dev.0"),
+                false,
+            ),
+            (
+                "1.2.3",
+                Premajor,
+                Some("beta"),
+                None,
+                Some("2.0.0-beta.0"),
+                false,
+            ),
+            (
+                "1.2.3-1",
+                Premajor,
+                Some("dev"),
+                Some(One),
+                Some("2.0.0-dev.1"),
+                false,
+            ),
+            (
+                "1.2.3-dev.bar",
+                Prerelease,
+                Some("dev"),
+                None,
+                Some("1.2.3-dev.0"),
+                false,
+            ),
+            (
+                "1.2.3-0",
+                Prerelease,
+                Some("1"),
+                None,
+                Some("1.2.3-1.0"),
+                false,
+            ),
+            (
+                "1.2.3-1.0",
+                Prerelease,
+                Some("1"),
+                None,
+                Some("1.2.3-1.1"),
+                false,
+            ),
+            (
+                "1.2.3-1.1",
+                Prerelease,
+                Some("2"),
+                None,
+                Some("1.2.3-2.0"),
+                false,
+            ),
+            ("1.2.0", Prerelease, None, Some(One), Some("1.2.1-1"), false),
+            (
+                "1.2.1-1",
+                Prerelease,
+                None,
+                Some(One),
+                Some("1.2.1-2"),
+                false,
+            ),
+            (
+                "1.2.0",
+                Prerelease,
+                Some("dev"),
+                Some(Zero),
+                Some("1.2.1-dev.0"),
+                false,
+            ),
+            (
+                "1.2.0",
+                Prerelease,
+                Some("dev"),
+                Some(Omitted),
+                Some("1.2.1-dev"),
+                false,
+            ),
+            (
+                "1.2.0-dev",
+                Premajor,
+                Some("dev"),
+                Some(Omitted),
+                Some("2.0.0-dev"),
+                false,
+            ),
+            (
+                "1.2.0-dev",
+                Preminor,
+                Some("dev"),
+                Some(Omitted),
+                Some("1.3.0-dev"),
+                false,
+            ),
+            (
+                "1.2.0-dev",
+                Prepatch,
+                Some("dev"),
+                Some(Omitted),
+                Some("1.2.1-dev"),
+                false,
+            ),
+            (
+                "1.2.0-dev.1",
+                Prerelease,
+                Some("dev"),
+                Some(Omitted),
+                Some("1.2.0-dev.2"),
+                false,
+            ),
+            (
+                "1.2.0-dev",
+                Prerelease,
+                Some("dev"),
+                Some(Omitted),
+                None,
+                false,
+            ),
+            ("1.2.0", Prerelease, None, Some(Omitted), None, false),
+            (
+                "1.2.0",
+                Prerelease,
+                Some("invalid/identifier"),
+                None,
+                None,
+                false,
+            ),
+            ("1.2.3-beta", Release, None, None, Some("1.2.3"), false),
+            (
+                "1.2.3-beta.1+build",
+                Release,
+                None,
+                None,
+                Some("1.2.3"),
+                false,
+            ),
+            ("1.2.3", Release, None, None, None, false),
+            ("1.2.3", Prerelease, Some("01"), None, None, false),
+            (
+                "1.2.3",
+                Prerelease,
+                Some("01"),
+                None,
+                Some("1.2.4-01.0"),
+                true,
+            ),
+            (
+                "1.2.4-01.0",
+                Prerelease,
+                Some("01"),
+                None,
+                Some("1.2.4-1.1"),
+                true,
+            ),
+            (
+                "1.2.3",
+                Preminor,
+                Some("dev.01"),
+                None,
+                Some("1.3.0-dev.01.0"),
+                true,
+            ),
+            ("1.2.3", Prerelease, Some("invalid/id"), None, None, true),
+        ];
+
+        for (ver, release, identifier, base, expected, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::inc(ver, release, identifier, base, Some(opts)).ok();
+            assert_eq!(
+                res.as_deref(),
+                expected,
+                "testing inc: {} {} {:?} {:?} loose: {}",
+                ver,
+                release,
+                identifier,
+                base,
+                loose
+            );
+        }
+    }
+
+    #[test]
+    fn diff() {
+        use ReleaseType::*;
+
+        let v = vec![
+            ("1.2.3", "0.2.3", Some(Major)),
+            ("0.2.3", "1.2.3", Some(Major)),
+            ("1.4.5", "0.2.3", Some(Major)),
+            ("1.2.3", "2.0.0-pre", Some(Premajor)),
+            ("2.0.0-pre", "1.2.3", Some(Premajor)),
+            ("1.2.3", "1.3.3", Some(Minor)),
+            ("1.0.1", "1.1.0-pre", Some(Preminor)),
+            ("1.2.3", "1.2.4", Some(Patch)),
+            ("1.2.3", "1.2.4-pre", Some(Prepatch)),
+            ("0.0.1", "0.0.1-pre", Some(Patch)),
+            ("0.0.1", "0.0.1-pre-2", Some(Patch)),
+            ("1.1.0", "1.1.0-pre", Some(Minor)),
+            ("1.1.0-pre-1", "1.1.0-pre-2", Some(Prerelease)),
+            ("1.0.0", "1.0.0", None),
+            ("1.0.0-1", "1.0.0-1", None),
+            ("1.0.0+build", "1.0.0", None),
+            ("0.0.2-1", "0.0.2", Some(Patch)),
+            ("0.0.2-1", "0.0.3", Some(Patch)),
+            ("0.0.2-1", "0.1.0", Some(Minor)),
+            ("0.0.2-1", "1.0.0", Some(Major)),
+            ("0.1.0-1", "0.1.0", Some(Minor)),
+            ("1.0.0-1", "1.0.0", Some(Major)),
+            ("1.0.0-1", "1.1.1", Some(Major)),
+            ("1.0.0-1", "2.1.1", Some(Major)),
+            ("1.0.1-1", "1.0.1", Some(Patch)),
+            ("0.0.0-1", "0.0.0", Some(Major)),
+            ("1.0.0-1", "2.0.0", Some(Major)),
+            ("1.0.0-1", "2.0.0-1", Some(Premajor)),
+            ("1.0.0-1", "1.1.0-1", Some(Preminor)),
+            ("1.0.0-1", "1.0.1-1", Some(Prepatch)),
+            ("1.7.2-1", "1.8.1", Some(Minor)),
+            ("1.1.1-pre", "2.1.1-pre", Some(Premajor)),
+            ("1.1.1-pre", "2.1.1", Some(Major)),
+            ("1.2.3-1", "1.2.3", Some(Patch)),
+            ("1.4.0-1", "2.3.5", Some(Major)),
+            ("1.6.1-5", "1.7.2", Some(Minor)),
+            ("2.0.0-1", "2.1.1", Some(Major)),
+        ];
+
+        for (v1, v2, expected) in v {
+            let res = super::diff(v1, v2, None).unwrap();
+            assert_eq!(res, expected, "testing diff: {} {}", v1, v2);
+        }
+    }
+
+    #[test]
+    fn coerce() {
+        let max = "9".repeat(16);
+        let long = "1".repeat(17);
+        let safe = "1".repeat(16);
+
+        let v = vec![
+            (".1".to_owned(), "1.0.0".to_owned()),
+            (".1.".to_owned(), "1.0.0".to_owned()),
+            ("..1".to_owned(), "1.0.0".to_owned()),
+            (".1.1".to_owned(), "1.1.0".to_owned()),
+            ("1.".to_owned(), "1.0.0".to_owned()),
+            ("1.0".to_owned(), "1.0.0".to_owned()),
+            ("1.0.0".to_owned(), "1.0.0".to_owned()),
+            ("0".to_owned(), "0.0.0".to_owned()),
+            ("0.0".to_owned(), "0.0.0".to_owned()),
+            ("0.1".to_owned(), "0.1.0".to_owned()),
+            ("0.0.1".to_owned(), "0.0.1".to_owned()),
+            ("1.2.3.4".to_owned(), "1.2.3".to_owned()),
+            ("13".to_owned(), "13.0.0".to_owned()),
+            ("35.12".to_owned(), "35.12.0".to_owned()),
+            ("35.12.18.24".to_owned(), "35.12.18".to_owned()),
+            ("v1".to_owned(), "1.0.0".to_owned()),
+            ("v2".to_owned(), "2.0.0".to_owned()),
+            ("v1.2.3.4".to_owned(), "1.2.3".to_owned()),
+            (" 1".to_owned(), "1.0.0".to_owned()),
+            ("1 ".to_owned(), "1.0.0".to_owned()),
+            ("1 0".to_owned(), "1.0.0".to_owned()),
+            ("1.1 1".to_owned(), "1.1.0".to_owned()),
+            ("1.1-1".to_owned(), "1.1.0".to_owned()),
+            ("1.1--1".to_owned(), "1.1.0".to_owned()),
+            ("1.1.1-1".to_owned(), "1.1.1".to_owned()),
+            ("1.1.1+1".to_owned(), "1.1.1".to_owned()),
+            ("v1.1.1-1+1.1".to_owned(), "1.1.1".to_owned()),
+            ("3.4-beta".to_owned(), "3.4.0".to_owned()),
+            ("release-1.2.3.4".to_owned(), "1.2.3".to_owned()),
+            ("openssl 1.1.1k".to_owned(), "1.1.1".to_owned()),
+            ("a1a2a3".to_owned(), "1.0.0".to_owned()),
+            (safe.clone(), format!("{}.0.0", safe)),
+            (format!("a{}", safe), format!("{}.0.0", safe)),
+            (format!("{}.2.3.4", safe), format!("{}.2.3", safe)),
+            (format!("1.{}.3", safe), format!("1.{}.3", safe)),
+        ];
+        for (input, output) in v {
+            let res = super::coerce(&input, CoerceOptions::default());
+            assert_eq!(
+                res.map(|v| v.to_string()).as_deref(),
+                Some(output.as_str()),
+                "testing coerce: {}",
+                input
+            );
+        }
+
+        let v = vec![
+            "".to_owned(),
+            ".".to_owned(),
+            "version one".to_owned(),
+            max.clone(),
+            long.clone(),
+            format!("a{}", long),
+            format!("{}a", long),
+            format!("{}.4.7.4", max),
+            format!("1.{}.3", max),
+        ];
+        for input in v {
+            let res = super::coerce(&input, CoerceOptions::default());
+            assert!(res.is_none(), "testing coerce to none: {}", input);
+        }
+    }
+
+    #[test]
+    fn coerce_with_options() {
+        // input, output, rtl, include_prerelease
+        let v = vec![
+            ("1.2.3.4", "2.3.4", true, false),
+            ("1.2.3.4.5", "3.4.5", true, false),
+            ("1.2.3.4.5.6", "4.5.6", true, false),
+            ("10.11.12.13", "11.12.13", true, false),
+            ("1.2.3/a/b/c/2.3.4", "2.3.4", true, false),
+            ("1.2.3.4/a/b/c", "2.3.4", true, false),
+            ("1.2.3", "1.2.3", true, false),
+            ("1", "1.0.0", true, false),
+            ("1-rc.5", "1.0.0-rc.5", false, true),
+            ("1.2-rc.5", "1.2.0-rc.5", false, true),
+            ("1.2.3-rc.5", "1.2.3-rc.5", false, true),
+            ("1.2.3-rc.5/a", "1.2.3-rc.5", false, true),
+            ("1.2.3.4-rc.5", "1.2.3", false, true),
+            ("1+rev.f", "1.0.0+rev.f", false, true),
+            ("1.2+rev.f", "1.2.0+rev.f", false, true),
+            ("1.2.3+rev.f", "1.2.3+rev.f", false, true),
+            ("1.2.3-rc.5+rev.f", "1.2.3-rc.5+rev.f", false, true),
+            ("1.2.3.4-rc.5+rev.f", "1.2.3", false, true),
+            ("1.2.3.4-rc.5", "2.3.4-rc.5", true, true),
+            ("1.2.3.4-rc.5+rev.f", "2.3.4-rc.5+rev.f", true, true),
+        ];
+
+        for (input, output, rtl, include_prerelease) in v {
+            let opts = CoerceOptions {
+                rtl,
+                include_prerelease,
+            };
+            let res = super::coerce(input, opts).map(|v| v.to_string());
+            assert_eq!(
+                res.as_deref(),
+                Some(output),
+                "testing coerce: {} rtl: {} include_prerelease: {}",
+                input,
+                rtl,
+                include_prerelease
+            );
+        }
+    }
+
+    #[test]
+    fn max_satisfying() {
+        let v = vec![
+            (vec!["1.2.3", "1.2.4"], "1.2", Some("1.2.4"), false),
+            (vec!["1.2.4", "1.2.3"], "1.2", Some("1.2.4"), false),
+            (
+                vec!["1.2.3", "1.2.4", "1.2.5", "1.2.6"],
+                "~1.2.3",
+                Some("1.2.6"),
+                false,
+            ),
+            (
+                vec![
+                    "1.1.0", "1.2.0", "1.2.1", "1.3.0", "2.0.0b1", "2.0.0b2", "2.0.0b3", "2.0.0",
+                    "2.1.0",
+                ],
+                "~2.0.0",
+                Some("2.0.0"),
+                true,
+            ),
+            (vec!["1.2.3", "blerg", "1.2.4"], "1.2", Some("1.2.4"), false),
+            (vec!["1.2.3", "1.2.4"], "2.x", None, false),
+            (vec![], "*", None, false),
+        ];
+
+        for (versions, range, expected, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::max_satisfying(&versions, range, Some(opts)).unwrap();
+            assert_eq!(
+                res, expected,
+                "testing max_satisfying: {:?} {} loose: {}",
+                versions, range, loose
+            );
+        }
+    }
+
+    #[test]
+    fn min_satisfying() {
+        let v = vec![
+            (vec!["1.2.3", "1.2.4"], "1.2", Some("1.2.3"), false),
+            (vec!["1.2.4", "1.2.3"], "1.2", Some("1.2.3"), false),
+            (
+                vec!["1.2.3", "1.2.4", "1.2.5", "1.2.6"],
+                "~1.2.3",
+                Some("1.2.3"),
+                false,
+            ),
+            (
+                vec![
+                    "1.1.0", "1.2.0", "1.2.1", "1.3.0", "2.0.0b1", "2.0.0b2", "2.0.0b3", "2.0.0",
+                    "2.1.0",
+                ],
+                "~2.0.0",
+                Some("2.0.0"),
+                true,
+            ),
+            (vec!["blerg", "1.2.4", "1.2.3"], "1.2", Some("1.2.3"), false),
+            (vec!["1.2.3", "1.2.4"], "2.x", None, false),
+        ];
+
+        for (versions, range, expected, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::min_satisfying(&versions, range, Some(opts)).unwrap();
+            assert_eq!(
+                res, expected,
+                "testing min_satisfying: {:?} {} loose: {}",
+                versions, range, loose
+            );
+        }
+    }
+
+    #[test]
+    fn range_max_min_satisfying() {
+        let range = crate::Range::new("^1.2.0").parse().unwrap();
+        let versions = ["1.2.0+b", "1.2.0", "1.1.9", "1.9.2", "1.9.2+a", "2.0.0"]
+            .iter()
+            .map(|v| crate::Version::new(v).parse().unwrap())
+            .collect::<Vec<_>>();
+
+        let max = range.max_satisfying(&versions).unwrap();
+        assert_eq!(max.to_string(), "1.9.2");
+        let min = range.min_satisfying(&versions).unwrap();
+        assert_eq!(min.to_string(), "1.2.0+b");
+        assert!(range.max_satisfying(&versions[..0]).is_none());
+    }
+
+    #[test]
+    fn min_version() {
+        let v = vec![
+            // stars
+            ("*", Some("0.0.0")),
+            ("* || >=2", Some("0.0.0")),
+            (">=2 || *", Some("0.0.0")),
+            (">2 || *", Some("0.0.0")),
+            // equal
+            ("1.0.0", Some("1.0.0")),
+            ("1.0", Some("1.0.0")),
+            ("1.0.x", Some("1.0.0")),
+            ("1.0.*", Some("1.0.0")),
+            ("1", Some("1.0.0")),
+            ("1.x.x", Some("1.0.0")),
+            ("1.*.x", Some("1.0.0")),
+            ("1.x.*", Some("1.0.0")),
+            ("1.x", Some("1.0.0")),
+            ("1.*", Some("1.0.0")),
+            ("=1.0.0", Some("1.0.0")),
+            // tilde
+            ("~1.1.1", Some("1.1.1")),
+            ("~1.1.1-beta", Some("1.1.1-beta")),
+            ("~1.1.1 || >=2", Some("1.1.1")),
+            // caret
+            ("^1.1.1", Some("1.1.1")),
+            ("^1.1.1-beta", Some("1.1.1-beta")),
+            ("^1.1.1 || >=2", Some("1.1.1")),
+            ("^2.16.2 ^2.16", Some("2.16.2")),
+            // hyphen
+            ("1.1.1 - 1.8.0", Some("1.1.1")),
+            ("1.1 - 1.8.0", Some("1.1.0")),
+            // less than or equal
+            ("<2", Some("0.0.0")),
+            ("<0.0.0-beta", Some("0.0.0-0")),
+            ("<0.0.1-beta", Some("0.0.0")),
+            ("<2 || >4", Some("0.0.0")),
+            (">4 || <2", Some("0.0.0")),
+            ("<=2 || >=4", Some("0.0.0")),
+            (">=4 || <=2", Some("0.0.0")),
+            ("<0.0.0-beta >0.0.0-alpha", Some("0.0.0-alpha.0")),
+            (">0.0.0-alpha <0.0.0-beta", Some("0.0.0-alpha.0")),
+            // greater than or equal
+            (">=1.1.1 <2 || >=2.2.2 <2", Some("1.1.1")),
+            (">=2.2.2 <2 || >=1.1.1 <2", Some("1.1.1")),
+            // greater than
+            (">1.0.0", Some("1.0.1")),
+            (">1.0.0-0", Some("1.0.0-0.0")),
+            (">1.0.0-beta", Some("1.0.0-beta.0")),
+            (">2 || >1.0.0", Some("1.0.1")),
+            (">2 || >1.0.0-0", Some("1.0.0-0.0")),
+            (">2 || >1.0.0-beta", Some("1.0.0-beta.0")),
+            // impossible
+            (">4 <3", None),
+        ];
+
+        for (range, expected) in v {
+            let range_parsed = crate::Range::new(range).parse().unwrap();
+            let res = range_parsed.min_version().map(|v| v.to_string());
+            assert_eq!(res.as_deref(), expected, "testing min_version: {}", range);
+        }
+    }
+
+    #[test]
+    fn intersects() {
+        let v = vec![
+            // one is a version
+            ("1.3.0 || <1.0.0 >2.0.0", "1.3.0 || <1.0.0 >2.0.0", true),
+            ("<1.0.0 >2.0.0", ">0.0.0", false),
+            (">0.0.0", "<1.0.0 >2.0.0", false),
+            ("<1.0.0 >2.0.0", ">1.4.0 <1.6.0", false),
+            ("<1.0.0 >2.0.0", ">1.4.0 <1.6.0 || 2.0.0", false),
+            (">1.0.0 <=2.0.0", "2.0.0", true),
+            ("<1.0.0 >=2.0.0", "2.1.0", false),
+            ("<1.0.0 >=2.0.0", ">1.4.0 <1.6.0 || 2.0.0", false),
+            // two ranges
+            ("1.5.x", "<1.5.0 || >=1.6.0", false),
+            ("<1.5.0 || >=1.6.0", "1.5.x", false),
+            (
+                "<1.6.16 || >=1.7.0 <1.7.11 || >=1.8.0 <1.8.2",
+                ">=1.6.16 <1.7.0 || >=1.7.11 <1.8.0 || >=1.8.2",
+                false,
+            ),
+            (
+                "<=1.6.16 || >=1.7.0 <1.7.11 || >=1.8.0 <1.8.2",
+                ">=1.6.16 <1.7.0 || >=1.7.11 <1.8.0 || >=1.8.2",
+                true,
+            ),
+            (">=1.0.0", "<=1.0.0", true),
+            (">1.0.0 <1.0.0", "<=0.0.0", false),
+            ("*", "0.0.1", true),
+            ("*", ">=1.0.0", true),
+            ("*", ">1.0.0", true),
+            ("*", "~1.0.0", true),
+            ("*", "<1.6.0", true),
+            ("*", "1.6.0 - 1.9.0", true),
+            ("x", "*", true),
+            ("^1.2.3", "^1.4.0", true),
+            ("^1.2.3", "^2.0.0", false),
+            ("~1.2.3", "1.2.x", true),
+            ("1.0.0 - 2.0.0", "2.0.0 - 3.0.0", true),
+            ("1.0.0 - 2.0.0", ">2.0.0", false),
+        ];
+
+        for (r1, r2, expected) in v {
+            let range1 = crate::Range::new(r1).parse().unwrap();
+            let range2 = crate::Range::new(r2).parse().unwrap();
+            assert_eq!(
+                range1.intersects(&range2, None),
+                expected,
+                "testing intersects: {} {}",
+                r1,
+                r2
+            );
+            assert_eq!(
+                range2.intersects(&range1, None),
+                expected,
+                "testing intersects: {} {}",
+                r2,
+                r1
+            );
+        }
+    }
+
+    #[test]
+    fn subset() {
+        // sub, dom, expected, include_prerelease
+        let v = vec![
+            ("1.2.3", "1.2.3", true, false),
+            ("1.2.3", "1.x", true, false),
+            ("1.2.3 1.2.4", "1.2.3", true, false),
+            ("1.2.3 1.2.4", "1.2.9", true, false),
+            ("1.2.3", ">1.2.0", true, false),
+            ("1.2.3 2.3.4 || 2.3.4", "3", false, false),
+            ("^1.2.3-pre.0", "1.x", false, false),
+            ("^1.2.3-pre.0", "1.x", true, true),
+            (">2 <1", "3", true, false),
+            ("1 || 2 || 3", ">=1.0.0", true, false),
+            // everything is a subset of *
+            ("1.2.3", "*", true, false),
+            ("^1.2.3", "*", true, false),
+            ("^1.2.3-pre.0", "*", false, false),
+            ("^1.2.3-pre.0", "*", true, true),
+            ("1 || 2 || 3", "*", true, false),
+            ("*", "*", true, false),
+            ("", "*", true, false),
+            ("*", "", true, false),
+            ("", "", true, false),
+            // >=0.0.0 is like * in non-prerelease mode, >=0.0.0-0 is like * in prerelease mode
+            ("*", ">=0.0.0-0", true, true),
+            ("*", ">=0.0.0", true, false),
+            ("*", ">=0.0.0", false, true),
+            ("*", ">=0.0.0-0", true, false),
+            ("^2 || ^3 || ^4", ">=1", true, false),
+            ("^2 || ^3 || ^4", ">1", true, false),
+            ("^2 || ^3 || ^4", ">=2", true, false),
+            ("^2 || ^3 || ^4", ">=3", false, false),
+            (">=1", "^2 || ^3 || ^4", false, false),
+            (">1", "^2 || ^3 || ^4", false, false),
+            (">=2", "^2 || ^3 || ^4", false, false),
+            (">=3", "^2 || ^3 || ^4", false, false),
+            ("^1", "^2 || ^3 || ^4", false, false),
+            ("^2", "^2 || ^3 || ^4", true, false),
+            ("^3", "^2 || ^3 || ^4", true, false),
+            ("^4", "^2 || ^3 || ^4", true, false),
+            ("1.x", "1.2.3", false, false),
+            ("1.x", ">1.2.0", false, false),
+            ("~1.2.3", "^1.2.3", true, false),
+            ("^1.2.3", "~1.2.3", false, false),
+            (">=1.0.0 <=1.0.0", "1.0.0", true, false),
+            (">1.0.0 <1.0.1", "<2.0.0", true, false),
+            (">=1.0.0", "<2.0.0", false, false),
+            ("*", "<1.0.0", false, false),
+            (">=1.2.3-pre", ">=1.0.0", false, false),
+            (">=1.2.3-pre", ">=1.2.3-alpha", true, false),
+            // a range is always a subset of itself, prereleases included
+            ("~1.5.0-beta.2", "~1.5.0-beta.2", true, false),
+            (
+                ">=1.2.3-alpha <2.0.0-rc.1",
+                ">=1.2.3-alpha <2.0.0-rc.1",
+                true,
+                false,
+            ),
+            ("~1.2.3-beta", "~1.2.3-beta", true, false),
+            (
+                "^1.2.3-rc.1 || >=3.0.0-0 <3.0.1",
+                "^1.2.3-rc.1 || >=3.0.0-0 <3.0.1",
+                true,
+                false,
+            ),
+        ];
+
+        for (sub, dom, expected, include_prerelease) in v {
+            let opts = Options::builder()
+                .include_prerelease(include_prerelease)
+                .build();
+            let sub_range = crate::Range::new(sub).with_options(opts).parse().unwrap();
+            let dom_range = crate::Range::new(dom).with_options(opts).parse().unwrap();
+            assert_eq!(
+                sub_range.subset(&dom_range, opts),
+                expected,
+                "testing subset: {} {} include_prerelease: {}",
+                sub,
+                dom,
+                include_prerelease
+            );
+        }
+    }
+
+    #[test]
+    fn valid_range() {
+        let v = vec![
+            ("1.0.0 - 2.0.0", Some(">=1.0.0 <=2.0.0"), false),
+            ("1.0.0", Some("1.0.0"), false),
+            (">=*", Some("*"), false),
+            ("", Some("*"), false),
+            ("*", Some("*"), false),
+            (">=1.0.0", Some(">=1.0.0"), false),
+            (">1.0.0", Some(">1.0.0"), false),
+            ("<=2.0.0", Some("<=2.0.0"), false),
+            ("1", Some(">=1.0.0 <2.0.0-0"), false),
+            ("<2.0.0", Some("<2.0.0"), false),
+            (">= 1.0.0", Some(">=1.0.0"), false),
+            (">=  1.0.0", Some(">=1.0.0"), false),
+            (">=   1.0.0", Some(">=1.0.0"), false),
+            ("> 1.0.0", Some(">1.0.0"), false),
+            (">  1.0.0", Some(">1.0.0"), false),
+            ("<=   2.0.0", Some("<=2.0.0"), false),
+            ("<= 2.0.0", Some("<=2.0.0"), false),
+            ("<=  2.0.0", Some("<=2.0.0"), false),
+            ("<    2.0.0", Some("<2.0.0"), false),
+            ("<\t2.0.0", Some("<2.0.0"), false),
+            (">=0.1.97", Some(">=0.1.97"), false),
+            ("0.1.20 || 1.2.4", Some("0.1.20||1.2.4"), false),
+            (">=0.2.3 || <0.0.1", Some(">=0.2.3||<0.0.1"), false),
+            ("||", Some("*"), false),
+            ("2.x.x", Some(">=2.0.0 <3.0.0-0"), false),
+            ("1.2.x", Some(">=1.2.0 <1.3.0-0"), false),
+            (
+                "1.2.x || 2.x",
+                Some(">=1.2.0 <1.3.0-0||>=2.0.0 <3.0.0-0"),
+                false,
+            ),
+            ("x", Some("*"), false),
+            ("2.*.*", Some(">=2.0.0 <3.0.0-0"), false),
+            ("1.2.*", Some(">=1.2.0 <1.3.0-0"), false),
+            (
+                "1.2.* || 2.*",
+                Some(">=1.2.0 <1.3.0-0||>=2.0.0 <3.0.0-0"),
+                false,
+            ),
+            ("2", Some(">=2.0.0 <3.0.0-0"), false),
+            ("2.3", Some(">=2.3.0 <2.4.0-0"), false),
+            ("~2.4", Some(">=2.4.0 <2.5.0-0"), false),
+            ("~>3.2.1", Some(">=3.2.1 <3.3.0-0"), false),
+            ("~1", Some(">=1.0.0 <2.0.0-0"), false),
+            ("~>1", Some(">=1.0.0 <2.0.0-0"), false),
+            ("~> 1", Some(">=1.0.0 <2.0.0-0"), false),
+            ("~1.0", Some(">=1.0.0 <1.1.0-0"), false),
+            ("~ 1.0", Some(">=1.0.0 <1.1.0-0"), false),
+            ("^0", Some("<1.0.0-0"), false),
+            ("^ 1", Some(">=1.0.0 <2.0.0-0"), false),
+            ("^0.1", Some(">=0.1.0 <0.2.0-0"), false),
+            ("^1.0", Some(">=1.0.0 <2.0.0-0"), false),
+            ("^1.2", Some(">=1.2.0 <2.0.0-0"), false),
+            ("^0.0.1", Some(">=0.0.1 <0.0.2-0"), false),
+            ("^0.0.1-beta", Some(">=0.0.1-beta <0.0.2-0"), false),
+            ("^0.1.2", Some(">=0.1.2 <0.2.0-0"), false),
+            ("^1.2.3", Some(">=1.2.3 <2.0.0-0"), false),
+            ("^1.2.3-beta.4", Some(">=1.2.3-beta.4 <2.0.0-0"), false),
+            ("<1", Some("<1.0.0-0"), false),
+            ("< 1", Some("<1.0.0-0"), false),
+            (">=1", Some(">=1.0.0"), false),
+            (">= 1", Some(">=1.0.0"), false),
+            ("<1.2", Some("<1.2.0-0"), false),
+            ("< 1.2", Some("<1.2.0-0"), false),
+            (">01.02.03", Some(">1.2.3"), true),
+            (">01.02.03", None, false),
+            ("~1.2.3beta", Some(">=1.2.3-beta <1.3.0-0"), true),
+            ("~1.2.3beta", None, false),
+            ("^ 1.2 ^ 1", Some(">=1.2.0 <2.0.0-0 >=1.0.0"), false),
+            ("1.2 - 3.4.5", Some(">=1.2.0 <=3.4.5"), false),
+            ("1.2.3 - 3.4", Some(">=1.2.3 <3.5.0-0"), false),
+            ("1.2 - 3.4", Some(">=1.2.0 <3.5.0-0"), false),
+            (">1", Some(">=2.0.0"), false),
+            (">1.2", Some(">=1.3.0"), false),
+            (">X", Some("<0.0.0-0"), false),
+            ("<X", Some("<0.0.0-0"), false),
+            ("<x <* || >* 2.x", Some("<0.0.0-0"), false),
+            (">x 2.x || * || <x", Some("*"), false),
+            ("^", None, false),
+            ("~", None, false),
+            ("^v 1.2", None, false),
+            ("^v 1.2", Some(">=1.2.0 <1.3.0-0"), true),
+            ("* || ^1.2.3-beta", Some("*"), false),
+            (">=0.0.0", Some("*"), false),
+            ("<0.0.0 || 1.x", Some("<0.0.0||>=1.0.0 <2.0.0-0"), false),
+            ("garbage", None, true),
+            ("garbage || 1.2", Some(">=1.2.0 <1.3.0-0"), true),
+            ("^9007199254740991", None, false),
+            ("9007199254740992.0.0", None, false),
+        ];
+
+        for (range, expected, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::valid_range(range, Some(opts)).unwrap();
+            assert_eq!(
+                res.as_deref(),
+                expected,
+                "testing valid_range: {} loose: {}",
+                range,
+                loose
+            );
+        }
+    }
+
+    #[test]
+    fn valid() {
+        let v = vec![
+            ("1.2.3", Some("1.2.3"), false),
+            (" v1.2.3-beta+build ", Some("1.2.3-beta"), false),
+            ("=1.2.3", None, false),
+            ("=1.2.3", Some("1.2.3"), true),
+            ("1.2.3beta", None, false),
+            ("1.2.3beta", Some("1.2.3-beta"), true),
+            ("1.2", None, false),
+            ("garbage", None, false),
+            ("", None, false),
+        ];
+
+        for (input, expected, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::valid(input, Some(opts)).map(|v| v.to_string());
+            assert_eq!(
+                res.as_deref(),
+                expected,
+                "testing valid: {} loose: {}",
+                input,
+                loose
+            );
+        }
+
+        assert!(super::parse("garbage", None).is_err());
+        assert!(super::compare("garbage", "0.0.0", None).is_err());
+    }
+
+    #[test]
+    fn gtr() {
+        // range, version, loose
+        let v = vec![
+            ("~1.2.2", "1.3.0", false),
+            ("~0.6.1-1", "0.7.1-1", false),
+            ("1.0.0 - 2.0.0", "2.0.1", false),
+            ("1.0.0", "1.0.1-beta1", false),
+            ("1.0.0", "2.0.0", false),
+            ("<=2.0.0", "2.1.1", false),
+            ("<=2.0.0", "3.2.9", false),
+            ("<2.0.0", "2.0.0", false),
+            ("0.1.20 || 1.2.4", "1.2.5", false),
+            ("2.x.x", "3.0.0", false),
+            ("1.2.x", "1.3.0", false),
+            ("1.2.x || 2.x", "3.0.0", false),
+            ("2.*.*", "5.0.1", false),
+            ("1.2.*", "1.3.3", false),
+            ("1.2.* || 2.*", "4.0.0", false),
+            ("2", "3.0.0", false),
+            ("2.3", "2.4.2", false),
+            ("~2.4", "2.5.0", false),
+            ("~2.4", "2.5.5", false),
+            ("~>3.2.1", "3.3.0", false),
+            ("~1", "2.2.3", false),
+            ("~>1", "2.2.4", false),
+            ("~> 1", "3.2.3", false),
+            ("~1.0", "1.1.2", false),
+            ("~ 1.0", "1.1.0", false),
+            ("<1.2", "1.2.0", false),
+            ("< 1.2", "1.2.1", false),
+            ("1", "2.0.0beta", true),
+            ("~v0.5.4-pre", "0.6.0", false),
+            ("~v0.5.4-pre", "0.6.1-pre", false),
+            ("=0.7.x", "0.8.0", false),
+            ("=0.7.x", "0.8.0-asdf", false),
+            ("<0.7.x", "0.7.0", false),
+            ("<=2.0.0", "2.9999.9999", false),
+            ("<2.0.0", "2.2.9", false),
+            ("<1", "1.0.0beta", true),
+            ("< 1", "1.0.0beta", true),
+        ];
+
+        for (range, ver, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::gtr(ver, range, Some(opts)).unwrap();
+            assert!(res, "testing gtr: {} {} loose: {}", range, ver, loose);
+        }
+    }
+
+    #[test]
+    fn negative_gtr() {
+        // range, version, loose
+        let v = vec![
+            ("~0.6.1-1", "0.6.1-1", false),
+            ("1.0.0 - 2.0.0", "1.2.3", false),
+            ("1.0.0 - 2.0.0", "0.9.9", false),
+            ("1.0.0", "1.0.0", false),
+            ("", "1.0.0", true),
+            ("*", "1.2.3", false),
+            ("*", "v1.2.3-foo", false),
+            ("||", "1.3.4", false),
+            ("x", "1.2.3", false),
+            (">=1.0.0", "1.0.0", false),
+            (">1.0.0", "1.1.0", false),
+            ("<=2.0.0", "0.2.9", false),
+            ("<2.0.0", "1.9999.9999", false),
+            (">=0.1.97", "v0.1.97", false),
+            ("0.1.20 || 1.2.4", "1.2.3", false),
+            ("0.1.20 || >1.2.4", "1.2.4", false),
+            (">=0.2.3 || <0.0.1", "0.0.0", false),
+            (">=0.2.3 || <0.0.1", "0.2.4", false),
+            ("1.2.x || 2.x", "1.2.3", false),
+            ("~v0.5.4-pre", "0.5.4", false),
+            ("<=0.7.x", "0.6.2", false),
+            (">0.2.3 >0.2.4 <=0.2.5", "0.2.5", false),
+            ("^1", "0.0.0-0", false),
+            ("^3.0.0", "2.0.0", false),
+            ("^1.0.0 || ~2.0.1", "2.0.0", false),
+            ("^0.1.0 || ~3.0.1 || 5.0.0", "3.2.0", false),
+            ("^0.1.0 || ~3.0.1 || 5.0.0", "1.0.0beta", true),
+            ("^0.1.0 || ~3.0.1 || 5.0.0", "5.0.0-0", true),
+            ("^0.1.0 || ~3.0.1 || >4 <=5.0.0", "3.5.0", false),
+        ];
+
+        for (range, ver, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::gtr(ver, range, Some(opts)).unwrap();
+            assert!(
+                !res,
+                "testing negative gtr: {} {} loose: {}",
+                range, ver, loose
+            );
+        }
+    }
+
+    #[test]
+    fn ltr() {
+        // range, version, loose
+        let v = vec![
+            ("~1.2.2", "1.2.1", false),
+            ("~0.6.1-1", "0.6.1-0", false),
+            ("1.0.0 - 2.0.0", "0.0.1", false),
+            ("1.0.0-beta.2", "1.0.0-beta.1", false),
+            ("1.0.0", "0.0.0", false),
+            (">=2.0.0", "1.1.1", false),
+            (">2.0.0", "2.0.0", false),
+            ("0.1.20 || 1.2.4", "0.1.5", false),
+            ("2.x.x", "1.0.0", false),
+            ("1.2.x || 2.x", "1.0.0", false),
+            ("1.2.* || 2.*", "1.1.9999", false),
+            ("2.3", "2.2.2", false),
+            ("~2.4", "2.3.5", false),
+            ("~>3.2.1", "3.2.0", false),
+            ("~> 1", "0.2.3", false),
+            ("~ 1.0", "0.1.0", false),
+            (">1.2", "1.2.0", false),
+            ("> 1.2", "1.2.1", false),
+            ("1", "0.0.0beta", true),
+            ("~v0.5.4-pre", "0.5.4-alpha", false),
+            ("=0.7.x", "0.6.0-asdf", false),
+            (">=0.7.x", "0.6.0", false),
+            (">=2.0.0", "1.9999.9999", false),
+            (">1", "1.0.0beta", true),
+            ("=0.7.x", "0.7.0-asdf", false),
+            ("^1", "1.0.0-0", false),
+            (">1.2.3", "1.3.0-alpha", false),
+        ];
+
+        for (range, ver, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::ltr(ver, range, Some(opts)).unwrap();
+            assert!(res, "testing ltr: {} {} loose: {}", range, ver, loose);
+        }
+    }
+
+    #[test]
+    fn negative_ltr() {
+        // range, version, loose
+        let v = vec![
+            ("~ 1.0", "1.1.0", false),
+            ("~0.6.1-1", "0.6.1-1", false),
+            ("1.0.0 - 2.0.0", "1.2.3", false),
+            ("1.0.0 - 2.0.0", "2.9.9", false),
+            ("1.0.0", "1.0.0", false),
+            ("", "1.0.0", true),
+            ("*", "1.2.3", false),
+            (">=1.0.0", "1.1.0", false),
+            ("<=2.0.0", "0.2.9", false),
+            ("<2.0.0", "1.9999.9999", false),
+            ("0.1.20 || 1.2.4", "1.2.3", false),
+            (">=0.2.3 || <0.0.1", "0.0.0", false),
+            (">=0.2.3 || <0.0.1", "0.2.3", false),
+            ("||", "1.3.4", false),
+            ("2", "2.1.2", false),
+            ("~1.0", "1.0.2", false),
+            ("<1.2", "1.1.1", false),
+            ("~v0.5.4-pre", "0.5.5", false),
+            ("<=0.7.x", "0.6.2", false),
+            (">=0.2.3 <=0.2.4", "0.2.4", false),
+            ("^3.0.0", "4.0.0", false),
+            ("^1.0.0 || ~2.0.1", "2.0.0", false),
+            ("^0.1.0 || ~3.0.1 || 5.0.0", "3.2.0", false),
+            ("^0.1.0 || ~3.0.1 || >4 <=5.0.0", "3.5.0", false),
+            ("^1.0.0alpha", "1.0.0beta", true),
+            ("~1.0.0-alpha", "1.0.0beta", true),
+            ("^1.0.0-alpha", "1.0.0-beta", false),
+            ("=0.1.0", "1.0.0", false),
+        ];
+
+        for (range, ver, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            let res = super::ltr(ver, range, Some(opts)).unwrap();
+            assert!(
+                !res,
+                "testing negative ltr: {} {} loose: {}",
+                range, ver, loose
+            );
+        }
+    }
+
+    #[test]
+    fn simplify_range() {
+        let versions = [
+            "1.0.0", "1.0.1", "1.2.0", "1.0.2", "1.1.0", "2.0.0", "2.1.0", "3.0.0", "invalid",
+        ];
+
+        let v = vec![
+            ("1.0.0 || 1.0.1 || 1.0.2 || ^1.1.0", "<=1.2.0"),
+            ("^1.0.0", "^1.0.0"),
+            ("1.x", "1.x"),
+            (">=1.0.0", "*"),
+            ("*", "*"),
+            ("^2.0.0 || ^3.0.0", ">=2.0.0"),
+            (
+                "1.0.1 || 1.0.2 || 1.1.0 || 1.2.0 || 2.1.0",
+                "1.0.1 - 1.2.0 || 2.1.0",
+            ),
+            (
+                "1.0.1 || 1.1.0 || 1.2.0 || 2.1.0",
+                "1.0.1 || 1.1.0 - 1.2.0 || 2.1.0",
+            ),
+            (">=1.0.1 <1.1.0", "1.0.1 - 1.0.2"),
+            ("2.0.0", "2.0.0"),
+            ("~2.1.0", "2.1.0"),
+            ("4.x", ""),
+            ("<1.0.0", ""),
+            ("", ""),
+            (">=1.0.0  <1.1.0  ||  2.x", ">=1.0.0  <1.1.0  ||  2.x"),
+        ];
+
+        for (range, expected) in v {
+            let res = super::simplify_range(&versions, range, None).unwrap();
+            assert_eq!(res, expected, "testing simplify_range: {}", range);
+        }
+        assert!(super::simplify_range(&versions, "blerg", None).is_err());
+    }
+
+    #[test]
+    fn range_set_operations() {
+        // a, b, union, intersection
+        let v = vec![
+            (
+                "^1.2.0",
+                ">=1.5.0 <3.0.0",
+                ">=1.2.0 <3.0.0",
+                ">=1.5.0 <2.0.0-0",
+            ),
+            (
+                "^1.2.0",
+                "1.5.x || >=1.8.0",
+                ">=1.2.0",
+                ">=1.5.0 <1.6.0-0||>=1.8.0 <2.0.0-0",
+            ),
+            ("1.x", "2.x", ">=1.0.0 <3.0.0-0", "<0.0.0-0"),
+            (
+                "1.x",
+                "3.x",
+                ">=1.0.0 <2.0.0-0||>=3.0.0 <4.0.0-0",
+                "<0.0.0-0",
+            ),
+            ("<1.2.3", ">1.2.3", "<1.2.3||>1.2.3", "<0.0.0-0"),
+            ("<=1.2.3", ">=1.2.3", "", "1.2.3"),
+            ("*", "~1.2.3", "", ">=1.2.3 <1.3.0-0"),
+            (
+                "1.2.3 || 1.2.4",
+                "1.2.4 || 1.2.5",
+                "1.2.3||1.2.4||1.2.5",
+                "1.2.4",
+            ),
+            (">2.0.0 <1.0.0", "1.2.3", "1.2.3", "<0.0.0-0"),
+        ];
+
+        for (a, b, union, intersection) in v {
+            let a = crate::Range::new(a).parse().unwrap();
+            let b = crate::Range::new(b).parse().unwrap();
+            assert_eq!(a.union(&b).to_string(), union, "testing union: {} {}", a, b);
+            assert_eq!(b.union(&a).to_string(), union, "testing union: {} {}", b, a);
+            assert_eq!(
+                a.intersect(&b).to_string(),
+                intersection,
+                "testing intersect: {} {}",
+                a,
+                b
+            );
+            assert_eq!(
+                b.intersect(&a).to_string(),
+                intersection,
+                "testing intersect: {} {}",
+                b,
+                a
+            );
+        }
+
+        let v = vec![
+            ("^1.2.0", "<1.2.0||>=2.0.0-0"),
+            ("1.2.3", "<1.2.3||>1.2.3"),
+            ("<1.0.0 || >=2.0.0", ">=1.0.0 <2.0.0"),
+            ("*", "<0.0.0-0"),
+            ("<0.0.0-0", ""),
+        ];
+
+        for (range, expected) in v {
+            let range = crate::Range::new(range).parse().unwrap();
+            let complement = range.complement();
+            assert_eq!(
+                complement.to_string(),
+                expected,
+                "testing complement: {}",
+                range
+            );
+            assert_eq!(
+                complement.complement().to_string(),
+                range.to_string(),
+                "testing double complement: {}",
+                range
+            );
+
+            for ver in ["0.0.0", "1.0.0", "1.2.3", "1.5.0", "2.0.0", "3.1.4"] {
+                let ver = crate::Version::new(ver).parse().unwrap();
+                assert_ne!(
+                    range.test(&ver),
+                    complement.test(&ver),
+                    "testing complement: {} {}",
+                    range,
+                    ver
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn range_equality() {
+        use std::collections::HashSet;
+
+        let parse = |range: &str| crate::Range::new(range).parse().unwrap();
+        let set = [
+            "^1.2.3",
+            ">=1.2.3 <2.0.0-0",
+            "~1.2.3",
+            "1.2.x || 2.x",
+            "^1.2.3",
+        ]
+        .iter()
+        .map(|range| parse(range))
+        .collect::<HashSet<_>>();
+        assert_eq!(set.len(), 3);
+
+        // the order of the comparators and of the sets, and repeated sets don't matter
+        let v = vec![
+            ("<2.0.0 >=1.0.0", ">=1.0.0 <2.0.0"),
+            ("1.x || 2.x", "2.x || 1.x"),
+            ("^1.2.3 || ^1.2.3", "^1.2.3"),
+            (
+                "<2.0.0 >=1.0.0 || 3.x || 1.x",
+                "3.x || >=1.0.0 <2.0.0 || 1.x || 3.x",
+            ),
+        ];
+        for (a, b) in v {
+            assert_eq!(parse(a), parse(b), "testing equality: {} {}", a, b);
+            let set = vec![parse(a), parse(b)].into_iter().collect::<HashSet<_>>();
+            assert_eq!(set.len(), 1, "testing hash: {} {}", a, b);
+        }
+        assert_ne!(parse("1.x || 2.x"), parse("1.x"));
+        assert_ne!(parse(">=1.0.0 <2.0.0"), parse(">=1.0.0 <=2.0.0"));
+
+        let range = parse("^1.2.3");
+        assert_eq!(range.clone(), range);
+        let opts = Options::builder().include_prerelease(true).build();
+        let with_prerelease = crate::Range::new("^1.2.3")
+            .with_options(opts)
+            .parse()
+            .unwrap();
+        assert_ne!(range, with_prerelease);
+        let loose = Options::builder().loose(true).build();
+        assert_eq!(
+            range,
+            crate::Range::new("^1.2.3")
+                .with_options(loose)
+                .parse()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn is_equivalent() {
+        // a, b, equivalent
+        let v = vec![
+            ("^1.2.3", ">=1.2.3 <2.0.0", true),
+            ("^1.2.3", ">=1.2.3 <2.0.0-0", true),
+            ("~1.2.3", ">=1.2.3 <1.3.0", true),
+            ("1.x", "1.0.0 - 1", true),
+            ("1.x || >=1.5.0 <3.0.0", ">=1.0.0 <3.0.0", true),
+            ("<1.0.0 || >=1.0.0", "*", true),
+            ("*", "", true),
+            ("1.2.3 || 1.2.3", "1.2.3", true),
+            (">2.0.0 <1.0.0", "<0.0.0-0", true),
+            ("^1.2.3", "~1.2.3", false),
+            ("1.x", "1.x || 2.0.0", false),
+            (">=1.2.3-beta <2.0.0", ">=1.2.3 <2.0.0", false),
+            ("<2.0.0", "<=2.0.0", false),
+            (">1.2.2 <=1.2.3", "1.2.3", true),
+            (">=1.0.0-rc.1 <1.0.0 || 1.x", ">=1.0.0-rc.1 <2.0.0", true),
+            (">=1.2.3-beta <2.0.0", ">=1.2.3-beta.0 <2.0.0", false),
+            (">=1.2.3-beta <2.0.0", ">=1.2.3 <2.0.0-beta", false),
+        ];
+
+        for (a, b, expected) in v {
+            let a = crate::Range::new(a).parse().unwrap();
+            let b = crate::Range::new(b).parse().unwrap();
+            assert_eq!(
+                a.is_equivalent(&b),
+                expected,
+                "testing is_equivalent: {} {}",
+                a,
+                b
+            );
+            assert_eq!(
+                b.is_equivalent(&a),
+                expected,
+                "testing is_equivalent: {} {}",
+                b,
+                a
+            );
+        }
+
+        let opts = Options::builder().include_prerelease(true).build();
+        let parse = |range: &str| crate::Range::new(range).with_options(opts).parse().unwrap();
+        let range = parse("^1.2.3");
+        assert!(range.is_equivalent(&parse(">=1.2.3 <2.0.0-0")));
+        assert!(!range.is_equivalent(&parse(">=1.2.3 <2.0.0")));
+        assert!(!range.is_equivalent(&parse(">=1.2.3 || 1.5.x <2.0.0")));
+        assert!(range.is_equivalent(&parse(">=1.2.3 <1.5.0 || >=1.5.0 <2.0.0-0")));
+        assert!(!range.is_equivalent(&crate::Range::new("^1.2.3").parse().unwrap()));
+    }
 }
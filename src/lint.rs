@@ -0,0 +1,242 @@
+use crate::expressions::{
+    LINT_COMMA, LINT_HYPHEN, LINT_LEADING_V, LINT_REVERSED_OPERATOR, LINT_RUBY_TILDE,
+};
+use crate::range::Range;
+
+use std::fmt;
+use std::ops;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A common mistake found in a range by [Range::lint](crate::Range::lint).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Lint {
+    pub kind: LintKind,
+    /// The byte range of the mistake in the linted input.
+    pub span: ops::Range<usize>,
+    /// The text the `span` should be replaced with.
+    pub replacement: String,
+    /// The whole input with this mistake and all the others found fixed, and its spaces normalized,
+    /// e.g. `>=1.2.3 <2.0.0` for `=> 1.2.3, <2.0.0`. Use the `span` and `replacement` to only fix this one.
+    pub suggestion: String,
+}
+
+/// The kind of mistake a [Lint](crate::Lint) reports.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LintKind {
+    /// An operator written backwards, e.g. `=>1.2.3` instead of `>=1.2.3`.
+    ReversedOperator,
+    /// A hyphen range without spaces around the hyphen, e.g. `1.2.3-1.2.4` instead of `1.2.3 - 1.2.4`.
+    HyphenWithoutSpaces,
+    /// The Ruby style pessimistic operator, e.g. `~>1.2` instead of `~1.2`.
+    RubyTilde,
+    /// An empty comparator set, which matches any version, e.g. the trailing `||` of `^1.2.3 ||`.
+    EmptyComparatorSet,
+    /// Comparators separated by commas like Cargo does, e.g. `>=1.0, <2.0` instead of `>=1.0 <2.0`.
+    CommaSeparator,
+    /// A leading `v` in front of a version, e.g. `v1.2.3` instead of `1.2.3`. Only reported when linting strictly.
+    LeadingV,
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, did you mean `{}`?", self.kind, self.suggestion)
+    }
+}
+
+impl fmt::Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            LintKind::ReversedOperator => "operator written backwards",
+            LintKind::HyphenWithoutSpaces => "hyphen range without spaces",
+            LintKind::RubyTilde => "ruby style `~>` operator",
+            LintKind::EmptyComparatorSet => "empty comparator set matching any version",
+            LintKind::CommaSeparator => "comma separated comparators",
+            LintKind::LeadingV => "leading `v`",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+pub(crate) fn lint(input: &str, loose: bool) -> Vec<Lint> {
+    let mut lints = vec![];
+    let mut push = |kind, span: ops::Range<usize>, replacement: &str| {
+        lints.push(Lint {
+            kind,
+            span,
+            replacement: replacement.into(),
+            suggestion: String::new(),
+        });
+    };
+
+    for m in LINT_REVERSED_OPERATOR.find_iter(input) {
+        let replacement = m.as_str().chars().rev().collect::<String>();
+        push(LintKind::ReversedOperator, m.range(), &replacement);
+    }
+
+    for m in LINT_RUBY_TILDE.find_iter(input) {
+        push(LintKind::RubyTilde, m.range(), "~");
+    }
+
+    for m in LINT_COMMA.find_iter(input) {
+        push(LintKind::CommaSeparator, m.range(), " ");
+    }
+
+    // loose ranges are expected to be sloppier
+    if !loose {
+        for cap in LINT_LEADING_V.captures_iter(input) {
+            push(LintKind::LeadingV, cap.get(1).unwrap().range(), "");
+        }
+    }
+
+    for cap in LINT_HYPHEN.captures_iter(input) {
+        // `1.2.3-1.2` is a valid prerelease, only a full version on the right looks like a range
+        let components = |i: usize| cap.get(i).map_or(0, |m| m.as_str().split('.').count());
+        if components(1) < 3 || components(3) == 3 {
+            push(
+                LintKind::HyphenWithoutSpaces,
+                cap.get(2).unwrap().range(),
+                " - ",
+            );
+        }
+    }
+
+    // an empty set in between `||` matches anything, which is almost never what was meant
+    let sets = input.split("||").collect::<Vec<_>>();
+    if sets.len() > 1 && sets.iter().any(|set| !set.trim().is_empty()) {
+        let mut start = 0;
+        for (i, set) in sets.iter().enumerate() {
+            let end = start + set.len();
+            if set.trim().is_empty() {
+                // remove the set along with one of the `||` around it
+                let span = match i {
+                    0 => start..end + 2,
+                    _ => start - 2..end,
+                };
+                push(LintKind::EmptyComparatorSet, span, "");
+            }
+            start = end + 2;
+        }
+    }
+
+    lints.sort_by_key(|lint| lint.span.start);
+
+    // fix all the mistakes at once, leaving out the ones overlapping a mistake that's already fixed
+    let mut fixed = String::with_capacity(input.len());
+    let mut end = 0;
+    for lint in lints.iter() {
+        if lint.span.start < end {
+            continue;
+        }
+        fixed.push_str(&input[end..lint.span.start]);
+        fixed.push_str(&lint.replacement);
+        end = lint.span.end;
+    }
+    fixed.push_str(&input[end..]);
+
+    let suggestion = normalize(&fixed);
+    for lint in lints.iter_mut() {
+        lint.suggestion = suggestion.clone();
+    }
+
+    lints
+}
+
+// Normalizes the spaces of a range the same way parsing does, e.g. `>= 1.2.3  < 2.0.0` => `>=1.2.3 <2.0.0`.
+fn normalize(range: &str) -> String {
+    let range = Range::trim_operators(range.trim());
+    let range = Range::trim_tilde(&range);
+    let range = Range::trim_caret(&range);
+
+    Range::trim_spaces(&range).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lints() {
+        let lints = [
+            ("=>1.2.3", LintKind::ReversedOperator, 0..2, ">=1.2.3"),
+            (
+                "<2.0.0 =< 1.2.3",
+                LintKind::ReversedOperator,
+                7..9,
+                "<2.0.0 <=1.2.3",
+            ),
+            (
+                "1.2.3-1.2.4",
+                LintKind::HyphenWithoutSpaces,
+                5..6,
+                "1.2.3 - 1.2.4",
+            ),
+            ("1.2-2", LintKind::HyphenWithoutSpaces, 3..4, "1.2 - 2"),
+            ("~>1.2", LintKind::RubyTilde, 0..2, "~1.2"),
+            ("^ 1.x || ", LintKind::EmptyComparatorSet, 6..9, "^1.x"),
+            ("|| 1.x", LintKind::EmptyComparatorSet, 0..2, "1.x"),
+            (
+                ">= 1.0 , < 2.0",
+                LintKind::CommaSeparator,
+                6..9,
+                ">=1.0 <2.0",
+            ),
+            ("v1.2.3", LintKind::LeadingV, 0..1, "1.2.3"),
+            (">=v1.2.3", LintKind::LeadingV, 2..3, ">=1.2.3"),
+        ];
+
+        for (input, kind, span, suggestion) in lints.iter().cloned() {
+            let lints = lint(input, false);
+            assert_eq!(lints.len(), 1, "{:?}: {:?}", input, lints);
+            assert_eq!(lints[0].kind, kind, "{:?}", input);
+            assert_eq!(lints[0].span, span, "{:?}", input);
+            assert_eq!(lints[0].suggestion, suggestion, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn no_lints() {
+        let ranges = [
+            "",
+            "*",
+            "||",
+            ">=1.2.3 <2.0.0",
+            "1.2.3 - 1.2.4",
+            "1.2.3-1.2",
+            "1.2.3-beta.1",
+            "~1.2 || ^2.0.0",
+            "=1.2.3",
+        ];
+
+        for range in ranges.iter() {
+            assert_eq!(lint(range, false), vec![], "{:?}", range);
+        }
+        assert_eq!(lint("v1.2.3 || >=v2.0.0", true), vec![]);
+    }
+
+    #[test]
+    fn several_lints() {
+        let lints = lint("=> v1.2.3, <2.0.0 ||", false);
+        let kinds = lints.iter().map(|lint| lint.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                LintKind::ReversedOperator,
+                LintKind::LeadingV,
+                LintKind::CommaSeparator,
+                LintKind::EmptyComparatorSet
+            ]
+        );
+        for lint in lints.iter() {
+            assert_eq!(lint.suggestion, ">=1.2.3 <2.0.0", "{:?}", lint);
+        }
+
+        let lints = lint("=> v1.2.3, <2.0.0 ||", true);
+        assert_eq!(lints.len(), 3);
+        assert_eq!(lints[0].suggestion, ">=v1.2.3 <2.0.0");
+    }
+}
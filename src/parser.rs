@@ -0,0 +1,1054 @@
+use crate::comparator::Comparator;
+use crate::error::{Error, ParseError, ParseErrorKind};
+use crate::operator::Operator;
+use crate::prerelease::Prerelease;
+use crate::version::Version;
+use crate::version_ref::VersionRef;
+
+use std::ops;
+
+// The characters operators are made of, used to tell a mistyped operator apart from an illegal character.
+const OPERATOR_CHARS: &[u8] = b"<>=~^!";
+
+/// Parses a version, e.g. `v1.2.3-beta.1+build.5`.
+pub(crate) fn version(input: &str, loose: bool) -> Result<Version, Error> {
+    version_ref(input, loose).map(|v| v.to_owned())
+}
+
+/// Parses a version borrowing its prerelease and build from `input`, see [version].
+pub(crate) fn version_ref(input: &str, loose: bool) -> Result<VersionRef<'_>, Error> {
+    let trimmed = input.trim_start();
+    let offset = input.len() - trimmed.len();
+    Parser::new(trimmed.trim_end(), loose, false)
+        .version_ref()
+        .map_err(|f| f.offset(offset).error(input))
+}
+
+/// Parses a primitive comparator, e.g. `>=1.2.3`, an empty one matches any version.
+pub(crate) fn comparator(input: &str, loose: bool) -> Result<Comparator, Error> {
+    primitive_comparator(input, loose).map_err(|f| f.error(input))
+}
+
+/// Parses the comparator sets of a range, desugaring hyphen ranges, x-ranges, tildes and carets
+/// into primitive comparators, e.g. `^1.2.3 || 2.x` into `>=1.2.3 <2.0.0-0` and `>=2.0.0 <3.0.0-0`.
+/// Loose ranges leave out the sets whose comparators are all invalid, and are invalid when that leaves none.
+///
+/// Like node-semver, `include_prerelease` lowers the bounds of partial versions to their first prerelease,
+/// e.g. `^1.2` into `>=1.2.0-0 <2.0.0-0`.
+pub(crate) fn range(
+    input: &str,
+    loose: bool,
+    include_prerelease: bool,
+) -> Result<Vec<Vec<Comparator>>, Error> {
+    let mut sets = vec![];
+    // the first set that couldn't be parsed, reported when no set is left
+    let mut failure = None;
+    let mut start = 0;
+    for set in input.split("||") {
+        let offset = start + set.len() - set.trim_start().len();
+        match comparator_set(set.trim(), loose, include_prerelease) {
+            Ok(set) => sets.push(set),
+            Err(f) if loose && !f.is_fatal() => {
+                failure.get_or_insert(f.offset(offset));
+            }
+            Err(f) => return Err(f.offset(offset).error(input)),
+        }
+        start += set.len() + 2;
+    }
+
+    match failure {
+        Some(f) if sets.is_empty() => Err(f.error(input)),
+        _ => Ok(sets),
+    }
+}
+
+/// Desugars a single comparator of a range into primitive ones, e.g. `~1.2` into `>=1.2.0 <1.3.0-0`,
+/// and pushes them to `out`. Fails when the comparator isn't valid.
+pub(crate) fn desugar(
+    token: &str,
+    loose: bool,
+    include_prerelease: bool,
+    out: &mut Vec<Comparator>,
+) -> Result<(), Failure> {
+    let mut p = Parser::new(token, loose, true);
+    let res = match p.peek() {
+        Some(b'^') => {
+            p.pos += 1;
+            match p.at_end() {
+                // a lone `^` is the same as `*`
+                true => Ok(()),
+                false => p.sugared().and_then(|v| caret(v, include_prerelease, out)),
+            }
+        }
+        Some(b'~') => {
+            p.pos += 1;
+            let arrow = p.eat(b'>');
+            // node-semver trims `~>` followed by spaces down to `~`, e.g. `~> >1.2` is `~>1.2`
+            if arrow && p.input[p.pos..].starts_with(char::is_whitespace) {
+                p.eat(b'>');
+            }
+            match !arrow && p.at_end() {
+                // a lone `~` is the same as `*`
+                true => Ok(()),
+                false => p.sugared().and_then(|v| tilde(v, out)),
+            }
+        }
+        _ => p
+            .xrange()
+            .and_then(|(operator, v)| xrange(operator, v, include_prerelease, out)),
+    };
+
+    match res {
+        Err(f) if !f.is_fatal() && token.contains('*') => match stars(token, loose, out)? {
+            true => Ok(()),
+            false => Err(f),
+        },
+        res => res,
+    }
+}
+
+// A set of whitespace separated comparators, or a hyphen range. Loose sets drop the comparators
+// they can't parse, and only fail when all of them are invalid.
+fn comparator_set(
+    set: &str,
+    loose: bool,
+    include_prerelease: bool,
+) -> Result<Vec<Comparator>, Failure> {
+    let mut comparators = Vec::with_capacity(2);
+    match Parser::new(set, loose, false).hyphen() {
+        Ok(Some(hyphen)) => {
+            hyphen.desugar(loose, include_prerelease, &mut comparators)?;
+            return Ok(clean_set(comparators, include_prerelease));
+        }
+        Ok(None) => {}
+        // loose sets that aren't a valid hyphen range are parsed like any other, e.g. `1.2.3 - 2.3.4 - 5`
+        Err(f) if loose && !f.is_fatal() => {}
+        Err(f) => return Err(f),
+    }
+
+    // the first comparator that couldn't be parsed, reported when none of them could
+    let mut failure = None;
+    // whether any of the comparators were valid, an empty set matches any version
+    let mut valid = set.is_empty();
+    let mut pos = 0;
+    while pos < set.len() {
+        let start = pos;
+        let mut end = word_end(set, start);
+
+        // operators can be separated from their version by spaces, e.g. `>= 1.2.3` or `~ 1.2`
+        loop {
+            let next = skip_whitespace(set, end);
+            if next == set.len() || !joins(&set[start..end], &set[next..word_end(set, next)]) {
+                break;
+            }
+            end = word_end(set, next);
+        }
+
+        let token = &set[start..end];
+        let res = match token {
+            "-" => Err(Failure::new(
+                ParseErrorKind::DanglingHyphen,
+                0..1,
+                Some("hyphen ranges need a version on both sides, e.g. `1.2.3 - 2.3.4`"),
+            )),
+            // unlike comparators, ranges can't be a lone `^` or `~`
+            "^" | "~" => Err(Failure::new(
+                ParseErrorKind::MissingComponent,
+                1..1,
+                Some("expected a version after the operator"),
+            )),
+            _ => desugar(token, loose, include_prerelease, &mut comparators),
+        };
+        match res.map_err(|f| f.offset(start)) {
+            Ok(()) => valid = true,
+            // loose ranges drop the comparators they can't parse
+            Err(f) if loose && !f.is_fatal() => {
+                failure.get_or_insert(f);
+            }
+            Err(f) => return Err(f),
+        }
+
+        pos = skip_whitespace(set, end);
+    }
+
+    match failure {
+        Some(f) if !valid => Err(f),
+        _ => Ok(clean_set(comparators, include_prerelease)),
+    }
+}
+
+// Cleans up a desugared set the same way node-semver does: a set with a null set comparator is only that one,
+// duplicated comparators are dropped and so is `>=0.0.0`, or `>=0.0.0-0` including prereleases, which match any version.
+fn clean_set(comparators: Vec<Comparator>, include_prerelease: bool) -> Vec<Comparator> {
+    let mut set: Vec<Comparator> = Vec::with_capacity(comparators.len());
+    for c in comparators {
+        if c.is_null_set() {
+            return vec![c];
+        }
+        let v = &c.version;
+        let lowest = c.operator == Operator::Gte
+            && !v.is_any()
+            && (v.major, v.minor, v.patch) == (0, 0, 0)
+            && match v.prerelease {
+                Some(ref pre) if !pre.is_empty() => include_prerelease && pre.is_zero(),
+                _ => !include_prerelease,
+            };
+        if !lowest && !set.contains(&c) {
+            set.push(c);
+        }
+    }
+
+    set
+}
+
+fn word_end(s: &str, start: usize) -> usize {
+    s[start..]
+        .find(char::is_whitespace)
+        .map_or(s.len(), |i| start + i)
+}
+
+fn skip_whitespace(s: &str, start: usize) -> usize {
+    s.len() - s[start..].trim_start().len()
+}
+
+// Whether the `word` ends with an operator that takes the `next` word as its version. The same way
+// node-semver trims the spaces around operators, `<`, `>` and `=` only take something that looks like a version.
+fn joins(word: &str, next: &str) -> bool {
+    let version_like = || {
+        let next = next.trim_start_matches(&['v', '='][..]);
+        matches!(next.bytes().next(), Some(b'0'..=b'9' | b'x' | b'X' | b'*'))
+    };
+
+    match word.as_bytes() {
+        [.., b'~'] | [.., b'^'] | [.., b'~', b'>'] => true,
+        [.., b'<' | b'>'] | [.., b'<' | b'>', b'='] | [b'='] => version_like(),
+        // `v=` and `==` are the prefix of a version rather than an operator
+        [.., c, b'='] => *c != b'v' && *c != b'=' && version_like(),
+        _ => false,
+    }
+}
+
+fn primitive_comparator(input: &str, loose: bool) -> Result<Comparator, Failure> {
+    if input.is_empty() {
+        return Ok(Comparator::from_parts(Operator::Empty, Version::any()));
+    }
+
+    let mut p = Parser::new(input, loose, false);
+    let operator = p.operator();
+    p.whitespace();
+    check_prefix(p.prefix(loose), loose)?;
+    let v = p.version(false)?;
+    p.end()?;
+    Ok(Comparator::from_parts(operator, v.version(false)?))
+}
+
+// Strips the stars from a comparator along with their operators, e.g. `>=*`,
+// the same way node-semver does with the comparators it can't otherwise parse.
+fn stars(token: &str, loose: bool, out: &mut Vec<Comparator>) -> Result<bool, Failure> {
+    let mut stripped = String::with_capacity(token.len());
+    // where the last star was, the operators before it are already gone
+    let mut star = 0;
+    for c in token.chars().filter(|c| !c.is_whitespace()) {
+        if c != '*' {
+            stripped.push(c);
+            continue;
+        }
+
+        if stripped.len() > star && stripped.ends_with('=') {
+            stripped.pop();
+        }
+        if stripped.len() > star && stripped.ends_with(&['<', '>'][..]) {
+            stripped.pop();
+        }
+        star = stripped.len();
+    }
+
+    if stripped.is_empty() {
+        return Ok(true);
+    }
+
+    match primitive_comparator(&stripped, loose) {
+        Ok(comparator) => {
+            out.push(comparator);
+            Ok(true)
+        }
+        // the stripped comparator isn't part of the input, so point at the whole token
+        Err(f) if f.is_fatal() => Err(Failure {
+            span: 0..token.len(),
+            ..f
+        }),
+        Err(_) => Ok(false),
+    }
+}
+
+fn caret(v: Partial, include_prerelease: bool, out: &mut Vec<Comparator>) -> Result<(), Failure> {
+    let z = lowest(include_prerelease);
+    let (major, minor, patch) = match (v.major, v.minor, v.patch) {
+        (None, _, _) => return Ok(()),
+        (Some(major), None, _) => {
+            out.push(primitive(Operator::Gte, major.value()?, 0, 0, z));
+            out.push(upper(major.inc()?, 0, 0));
+            return Ok(());
+        }
+        (Some(major), Some(minor), None) => {
+            out.push(primitive(
+                Operator::Gte,
+                major.value()?,
+                minor.value()?,
+                0,
+                z,
+            ));
+            out.push(match major.digits {
+                "0" => upper(0, minor.inc()?, 0),
+                _ => upper(major.inc()?, 0, 0),
+            });
+            return Ok(());
+        }
+        (Some(major), Some(minor), Some(patch)) => (major, minor, patch),
+    };
+
+    // node-semver only lowers full versions to their first prerelease for `0` majors
+    let prerelease = match major.digits {
+        "0" => v.prerelease.or(z),
+        _ => v.prerelease,
+    };
+    let (maj, min, pat) = v.numbers()?;
+    out.push(primitive(Operator::Gte, maj, min, pat, prerelease));
+    out.push(match (major.digits, minor.digits) {
+        ("0", "0") => upper(maj, min, patch.inc()?),
+        ("0", _) => upper(maj, minor.inc()?, 0),
+        _ => upper(major.inc()?, 0, 0),
+    });
+
+    Ok(())
+}
+
+fn tilde(v: Partial, out: &mut Vec<Comparator>) -> Result<(), Failure> {
+    match (v.major, v.minor, v.patch) {
+        (None, _, _) => {}
+        (Some(major), None, _) => {
+            out.push(primitive(Operator::Gte, major.value()?, 0, 0, None));
+            out.push(upper(major.inc()?, 0, 0));
+        }
+        (Some(major), Some(minor), patch) => {
+            let patch = patch.map(|p| p.value()).transpose()?;
+            let major = major.value()?;
+            out.push(primitive(
+                Operator::Gte,
+                major,
+                minor.value()?,
+                patch.unwrap_or(0),
+                patch.and(v.prerelease),
+            ));
+            out.push(upper(major, minor.inc()?, 0));
+        }
+    }
+
+    Ok(())
+}
+
+fn xrange(
+    operator: Operator,
+    v: Partial,
+    include_prerelease: bool,
+    out: &mut Vec<Comparator>,
+) -> Result<(), Failure> {
+    let z = lowest(include_prerelease);
+    let major = match v.major {
+        Some(major) => major,
+        // nothing can be lower or higher than any version
+        None if operator == Operator::Lt || operator == Operator::Gt => {
+            out.push(upper(0, 0, 0));
+            return Ok(());
+        }
+        None => return Ok(()),
+    };
+
+    match (v.minor, v.patch) {
+        (Some(_), Some(_)) => out.push(Comparator::from_parts(operator, v.version(false)?)),
+        (minor, _) if operator != Operator::Empty => {
+            let (operator, major, minor) = match (operator, minor) {
+                (Operator::Gt, None) => (Operator::Gte, major.inc()?, 0),
+                (Operator::Gt, Some(minor)) => (Operator::Gte, major.value()?, minor.inc()?),
+                (Operator::Lte, None) => (Operator::Lt, major.inc()?, 0),
+                (Operator::Lte, Some(minor)) => (Operator::Lt, major.value()?, minor.inc()?),
+                (operator, minor) => {
+                    let minor = minor.map(|m| m.value()).transpose()?;
+                    (operator, major.value()?, minor.unwrap_or(0))
+                }
+            };
+            let prerelease = match operator {
+                Operator::Lt => Some("0"),
+                _ => z,
+            };
+            out.push(primitive(operator, major, minor, 0, prerelease));
+        }
+        (None, _) => {
+            out.push(primitive(Operator::Gte, major.value()?, 0, 0, z));
+            out.push(upper(major.inc()?, 0, 0));
+        }
+        (Some(minor), None) => {
+            let major = major.value()?;
+            out.push(primitive(Operator::Gte, major, minor.value()?, 0, z));
+            out.push(upper(major, minor.inc()?, 0));
+        }
+    }
+
+    Ok(())
+}
+
+fn primitive(
+    operator: Operator,
+    major: u64,
+    minor: u64,
+    patch: u64,
+    prerelease: Option<&str>,
+) -> Comparator {
+    Comparator::from_parts(
+        operator,
+        Version::from_parts(major, minor, patch, prerelease.map(|p| p.into()), None),
+    )
+}
+
+// The exclusive upper bound of a desugared range, which like node-semver leaves out
+// the prereleases of the version it's made of, e.g. the `<2.0.0-0` of `^1.2.3`.
+fn upper(major: u64, minor: u64, patch: u64) -> Comparator {
+    primitive(Operator::Lt, major, minor, patch, Some("0"))
+}
+
+// The prerelease lowering the bounds of partial versions to include their prereleases, e.g. the `-0` of `>=1.2.0-0`.
+fn lowest(include_prerelease: bool) -> Option<&'static str> {
+    match include_prerelease {
+        true => Some("0"),
+        false => None,
+    }
+}
+
+// Strict versions can only be prefixed by a `v`, points at whatever else there is, e.g. the `=` of `=1.2.3`.
+fn check_prefix(prefix: Prefix, loose: bool) -> Result<(), Failure> {
+    if loose || prefix.text.is_empty() || prefix.text == "v" {
+        return Ok(());
+    }
+
+    let i = usize::from(prefix.text.starts_with('v'));
+    let len = prefix.text[i..].chars().next().map_or(1, char::len_utf8);
+    Err(Failure::new(
+        ParseErrorKind::IllegalCharacter,
+        prefix.start + i..prefix.start + i + len,
+        Some("versions can only be prefixed by a single `v`"),
+    ))
+}
+
+/// Why and where parsing failed, relative to the input of the parser it came from.
+/// Turned into a [ParseError](crate::ParseError) once the whole input is known.
+#[derive(Debug)]
+pub(crate) struct Failure {
+    kind: ParseErrorKind,
+    span: ops::Range<usize>,
+    hint: Option<&'static str>,
+}
+
+impl Failure {
+    fn new(kind: ParseErrorKind, span: ops::Range<usize>, hint: Option<&'static str>) -> Self {
+        Failure { kind, span, hint }
+    }
+
+    // Numbers too large are errors even where invalid comparators are dropped, like node-semver
+    // which rejects numbers it can't represent instead of ignoring them.
+    fn is_fatal(&self) -> bool {
+        self.kind == ParseErrorKind::Overflow
+    }
+
+    fn offset(mut self, offset: usize) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self
+    }
+
+    fn error(self, input: &str) -> Error {
+        Error::Parse(ParseError {
+            kind: self.kind,
+            input: input.into(),
+            span: self.span,
+            hint: self.hint.map(|h| h.into()),
+        })
+    }
+}
+
+/// A numeric component of a version and where it starts in the input, e.g. the `2` of `1.2.3`.
+/// Its value is only read when needed, loose ranges drop the comparators they can't parse whatever their numbers.
+#[derive(Clone, Copy, Debug)]
+struct Number<'a> {
+    digits: &'a str,
+    start: usize,
+}
+
+impl<'a> Number<'a> {
+    fn value(&self) -> Result<u64, Failure> {
+        self.digits.parse().map_err(|_| self.overflow())
+    }
+
+    // The next version number, e.g. the `3` of `<1.3.0` in `~1.2`.
+    fn inc(&self) -> Result<u64, Failure> {
+        self.value()?.checked_add(1).ok_or_else(|| self.overflow())
+    }
+
+    fn overflow(&self) -> Failure {
+        Failure::new(
+            ParseErrorKind::Overflow,
+            self.start..self.start + self.digits.len(),
+            Some("version numbers must fit in a 64 bit unsigned integer"),
+        )
+    }
+}
+
+/// The `v`s and `=`s in front of a version and where they start, e.g. the `v` of `v1.2.3`.
+#[derive(Clone, Copy, Debug)]
+struct Prefix<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+/// A version whose components may be x-ranges or left out, in which case they're `None`, e.g. `1.x` or `1.2.3-beta`.
+#[derive(Debug)]
+struct Partial<'a> {
+    major: Option<Number<'a>>,
+    minor: Option<Number<'a>>,
+    patch: Option<Number<'a>>,
+    prerelease: Option<&'a str>,
+    build: Option<&'a str>,
+}
+
+impl<'a> Partial<'a> {
+    fn is_full(&self) -> bool {
+        self.major.is_some() && self.minor.is_some() && self.patch.is_some()
+    }
+
+    // Only called for full versions.
+    fn numbers(&self) -> Result<(u64, u64, u64), Failure> {
+        let value = |n: Option<Number>| n.map_or(Ok(0), |n| n.value());
+        Ok((value(self.major)?, value(self.minor)?, value(self.patch)?))
+    }
+
+    // Only called for full versions.
+    fn version(&self, build: bool) -> Result<Version, Failure> {
+        let (major, minor, patch) = self.numbers()?;
+        Ok(Version::from_parts(
+            major,
+            minor,
+            patch,
+            self.prerelease.map(|p| p.into()),
+            self.build.filter(|_| build).map(|b| b.into()),
+        ))
+    }
+}
+
+/// A hyphen range, e.g. `1.2.3 - 2.3.4`.
+#[derive(Debug)]
+struct Hyphen<'a> {
+    from: Partial<'a>,
+    from_prefix: Prefix<'a>,
+    to: Partial<'a>,
+    to_prefix: Prefix<'a>,
+}
+
+impl<'a> Hyphen<'a> {
+    fn desugar(
+        self,
+        loose: bool,
+        include_prerelease: bool,
+        out: &mut Vec<Comparator>,
+    ) -> Result<(), Failure> {
+        let z = lowest(include_prerelease);
+
+        let from = &self.from;
+        match (from.major, from.minor, from.patch) {
+            (None, _, _) => {}
+            (Some(major), None, _) => out.push(primitive(Operator::Gte, major.value()?, 0, 0, z)),
+            (Some(major), Some(minor), None) => out.push(primitive(
+                Operator::Gte,
+                major.value()?,
+                minor.value()?,
+                0,
+                z,
+            )),
+            _ => {
+                // full versions are parsed like any other version, so only loose ones can have a prefix other than `v`
+                check_prefix(self.from_prefix, loose)?;
+                let mut v = from.version(false)?;
+                // node-semver appends the `-0` to the whole version, so a build swallows it
+                if v.prerelease.is_none() && from.build.is_none() {
+                    v.prerelease = z.map(Prerelease::from_parsed);
+                }
+                out.push(Comparator::from_parts(Operator::Gte, v))
+            }
+        }
+
+        let to = &self.to;
+        match (to.major, to.minor, to.patch) {
+            (None, _, _) => {}
+            (Some(major), None, _) => out.push(upper(major.inc()?, 0, 0)),
+            (Some(major), Some(minor), None) => out.push(upper(major.value()?, minor.inc()?, 0)),
+            _ if to.prerelease.is_some() => {
+                out.push(Comparator::from_parts(Operator::Lte, to.version(false)?))
+            }
+            // including prereleases, the ones of the next patch are left out too, e.g. `<2.3.5-0` for `2.3.4`
+            (Some(major), Some(minor), Some(patch)) if include_prerelease => {
+                out.push(upper(major.value()?, minor.value()?, patch.inc()?))
+            }
+            _ => {
+                check_prefix(self.to_prefix, loose)?;
+                out.push(Comparator::from_parts(Operator::Lte, to.version(false)?))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The characters prerelease and build identifiers are made of.
+fn is_identifier(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-'
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    loose: bool,
+    // Whether whitespace is ignored, the comparators of a range can only have it after their operator.
+    skip_whitespace: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, loose: bool, skip_whitespace: bool) -> Self {
+        Parser {
+            input,
+            pos: 0,
+            loose,
+            skip_whitespace,
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        if self.skip_whitespace {
+            self.whitespace();
+        }
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn at_end(&mut self) -> bool {
+        self.peek().is_none()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        match self.peek() == Some(b) {
+            true => {
+                self.pos += 1;
+                true
+            }
+            false => false,
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(u8) -> bool) -> &'a str {
+        self.peek();
+        let start = self.pos;
+        let bytes = self.input.as_bytes();
+        while self.pos < bytes.len() && f(bytes[self.pos]) {
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+
+    // Skips the whitespace at the current position, returning how much there was.
+    fn whitespace(&mut self) -> usize {
+        let start = self.pos;
+        self.pos = skip_whitespace(self.input, self.pos);
+        self.pos - start
+    }
+
+    // A failure for whatever is at the current position: a missing component at the end of the input,
+    // otherwise the character that can't be there.
+    fn unexpected(&mut self, hint: Option<&'static str>) -> Failure {
+        self.peek();
+        let pos = self.pos;
+        match self.input[pos..].chars().next() {
+            None => Failure::new(ParseErrorKind::MissingComponent, pos..pos, hint),
+            Some('|') if hint.is_none() => Failure::new(
+                ParseErrorKind::IllegalCharacter,
+                pos..pos + 1,
+                Some("comparator sets are separated by `||`"),
+            ),
+            Some(c) => Failure::new(
+                ParseErrorKind::IllegalCharacter,
+                pos..pos + c.len_utf8(),
+                hint,
+            ),
+        }
+    }
+
+    // Fails unless the whole input was parsed, pointing past the whitespace at what's left, e.g. the `4` of `1.2.3 4`.
+    fn end(&mut self) -> Result<(), Failure> {
+        if self.at_end() {
+            return Ok(());
+        }
+
+        let pos = self.pos;
+        if self.whitespace() > 0 && self.at_end() {
+            self.pos = pos;
+        }
+        Err(self.unexpected(None))
+    }
+
+    // `<`, `<=`, `>`, `>=`, `=` or nothing, which is the same as `=`.
+    fn operator(&mut self) -> Operator {
+        let operator = match self.peek() {
+            Some(b'<') => Operator::Lt,
+            Some(b'>') => Operator::Gt,
+            _ => Operator::Empty,
+        };
+        if operator != Operator::Empty {
+            self.pos += 1;
+        }
+
+        match (self.eat(b'='), operator) {
+            (true, Operator::Lt) => Operator::Lte,
+            (true, Operator::Gt) => Operator::Gte,
+            (_, operator) => operator,
+        }
+    }
+
+    // The `v`s and `=`s allowed in front of a version, along with spaces when `whitespace` is set, e.g. `v1.2.3`.
+    fn prefix(&mut self, whitespace: bool) -> Prefix<'a> {
+        self.peek();
+        let start = self.pos;
+        loop {
+            if whitespace {
+                self.whitespace();
+            }
+            if self.take_while(|b| b == b'v' || b == b'=').is_empty() {
+                break;
+            }
+        }
+
+        Prefix {
+            text: &self.input[start..self.pos],
+            start,
+        }
+    }
+
+    // Checks that the version of a comparator starts where the operator ends, to tell why it doesn't,
+    // e.g. the comparator `=>1.2.3` of a range starts with a mistyped operator.
+    fn expect_version(&mut self) -> Result<(), Failure> {
+        match self.peek() {
+            None => Err(self.unexpected(Some("expected a version after the operator"))),
+            Some(b'-') => Err(self.unexpected(Some(
+                "hyphen ranges need spaces around the `-`, e.g. `1.2.3 - 2.3.4`",
+            ))),
+            Some(b) if OPERATOR_CHARS.contains(&b) => {
+                let len = self.input.as_bytes()[self.pos..]
+                    .iter()
+                    .take_while(|b| OPERATOR_CHARS.contains(b))
+                    .count();
+                Err(Failure::new(
+                    ParseErrorKind::InvalidOperator,
+                    0..self.pos + len,
+                    Some("operators are one of `<`, `<=`, `>`, `>=`, `=`, `~`, `~>` and `^`"),
+                ))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    // A numeric component, strict ones can't have leading zeros.
+    fn number(&mut self) -> Result<Number<'a>, Failure> {
+        self.peek();
+        let start = self.pos;
+        let digits = self.take_while(|b| b.is_ascii_digit());
+        if digits.is_empty() {
+            return Err(self.unexpected(None));
+        } else if !self.loose && digits.len() > 1 && digits.starts_with('0') {
+            return Err(Failure::new(
+                ParseErrorKind::LeadingZero,
+                start..self.pos,
+                Some("version numbers can't have leading zeros"),
+            ));
+        }
+
+        Ok(Number { digits, start })
+    }
+
+    // A numeric component or, when `partial` is set, an x-range which is `None`, e.g. `1`, `x` or `*`.
+    fn component(&mut self, partial: bool) -> Result<Option<Number<'a>>, Failure> {
+        match self.peek() {
+            Some(b'x' | b'X' | b'*') if partial => {
+                self.pos += 1;
+                Ok(None)
+            }
+            _ => self.number().map(Some),
+        }
+    }
+
+    // The `.` in front of the minor or the patch, which partial versions can leave out along with the rest.
+    fn dot(&mut self, partial: bool) -> Result<bool, Failure> {
+        match self.eat(b'.') {
+            true => Ok(true),
+            false if partial => Ok(false),
+            false => Err(self.unexpected(None)),
+        }
+    }
+
+    // Dot separated identifiers, strict numeric prerelease identifiers can't have leading zeros.
+    fn identifiers(&mut self, prerelease: bool) -> Result<&'a str, Failure> {
+        self.peek();
+        let start = self.pos;
+        loop {
+            self.peek();
+            let identifier_start = self.pos;
+            let identifier = self.take_while(is_identifier);
+            if identifier.is_empty() {
+                return Err(match self.peek() {
+                    None => Failure::new(
+                        ParseErrorKind::EmptyIdentifier,
+                        identifier_start..identifier_start,
+                        Some("identifiers can't be empty"),
+                    ),
+                    Some(b'.') => Failure::new(
+                        ParseErrorKind::EmptyIdentifier,
+                        identifier_start..identifier_start + 1,
+                        Some("identifiers can't be empty"),
+                    ),
+                    Some(_) => self.unexpected(None),
+                });
+            }
+
+            let leading_zero = identifier.len() > 1
+                && identifier.starts_with('0')
+                && identifier.bytes().all(|b| b.is_ascii_digit());
+            if prerelease && !self.loose && leading_zero {
+                return Err(Failure::new(
+                    ParseErrorKind::LeadingZero,
+                    identifier_start..self.pos,
+                    Some("numeric identifiers can't have leading zeros"),
+                ));
+            }
+
+            let end = self.pos;
+            if !self.eat(b'.') {
+                return Ok(&self.input[start..end]);
+            }
+        }
+    }
+
+    // The prerelease after the patch, loose versions don't need a `-` in front of it, e.g. `1.2.3beta`.
+    fn prerelease(&mut self) -> Result<Option<&'a str>, Failure> {
+        if !self.loose {
+            return match self.eat(b'-') {
+                true => self.identifiers(true).map(Some),
+                false => Ok(None),
+            };
+        }
+
+        // a loose `-` is only a separator when an identifier follows it, e.g. `1.2.3-` has a `-` prerelease
+        let next = self.input.as_bytes().get(self.pos + 1).copied();
+        if self.peek() == Some(b'-') && next.is_some_and(is_identifier) {
+            self.pos += 1;
+        }
+        match self.peek() {
+            Some(b) if is_identifier(b) => self.identifiers(true).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn build(&mut self) -> Result<Option<&'a str>, Failure> {
+        match self.eat(b'+') {
+            true => self.identifiers(false).map(Some),
+            false => Ok(None),
+        }
+    }
+
+    // A `major.minor.patch` version with its prerelease and build. When `partial` is set the minor and patch
+    // may be left out and any component can be an x-range, e.g. `1` or `1.x`, then only full versions have
+    // a prerelease and a build.
+    fn version(&mut self, partial: bool) -> Result<Partial<'a>, Failure> {
+        let mut v = Partial {
+            major: self.component(partial)?,
+            minor: None,
+            patch: None,
+            prerelease: None,
+            build: None,
+        };
+
+        if self.dot(partial)? {
+            v.minor = self.component(partial)?;
+            if self.dot(partial)? {
+                v.patch = self.component(partial)?;
+                // loose prereleases don't need a `-`, so `1.2.31.2` is `1.2.3-1.2`
+                let bytes = self.input.as_bytes();
+                if let Some(patch) = v.patch.filter(|p| self.loose && p.digits.len() > 1) {
+                    if bytes.get(self.pos) == Some(&b'.') {
+                        self.pos -= 1;
+                        v.patch = Some(Number {
+                            digits: &patch.digits[..patch.digits.len() - 1],
+                            ..patch
+                        });
+                    }
+                }
+                v.prerelease = self.prerelease()?;
+                v.build = self.build()?;
+            }
+        }
+
+        Ok(v)
+    }
+
+    // A whole version, e.g. `v1.2.3-beta`.
+    fn version_ref(&mut self) -> Result<VersionRef<'a>, Failure> {
+        check_prefix(self.prefix(self.loose), self.loose)?;
+        let v = self.version(false)?;
+        self.end()?;
+
+        let (major, minor, patch) = v.numbers()?;
+        Ok(VersionRef {
+            major,
+            minor,
+            patch,
+            prerelease: v.prerelease,
+            build: v.build,
+        })
+    }
+
+    // The version after a `^`, `~` or `~>`.
+    fn sugared(&mut self) -> Result<Partial<'a>, Failure> {
+        self.prefix(false);
+        self.expect_version()?;
+        let v = self.version(true)?;
+        self.end()?;
+        Ok(v)
+    }
+
+    // A comparator with a partial version, e.g. `>=1.2` or `1.x`.
+    fn xrange(&mut self) -> Result<(Operator, Partial<'a>), Failure> {
+        let operator = self.operator();
+        let prefix = self.prefix(false);
+        self.expect_version()?;
+        let v = self.version(true)?;
+        self.end()?;
+        // full versions are parsed like any other version, so only loose ones can have a prefix other than `v`
+        if v.is_full() {
+            check_prefix(prefix, self.loose)?;
+        }
+        Ok((operator, v))
+    }
+
+    // A hyphen range, `None` when the input isn't one. Once past the ` - ` it can only be a hyphen range.
+    fn hyphen(&mut self) -> Result<Option<Hyphen<'a>>, Failure> {
+        let from_prefix = self.prefix(true);
+        let from = match self.version(true) {
+            Ok(from) => from,
+            Err(_) => return Ok(None),
+        };
+        if self.whitespace() == 0 || !self.eat(b'-') || self.whitespace() == 0 {
+            return Ok(None);
+        }
+
+        let to_prefix = self.prefix(true);
+        let to = self.version(true)?;
+        if !self.at_end() {
+            let hint = match self.whitespace() {
+                0 => None,
+                _ => Some("a hyphen range can't be combined with other comparators"),
+            };
+            return Err(self.unexpected(hint));
+        }
+
+        Ok(Some(Hyphen {
+            from,
+            from_prefix,
+            to,
+            to_prefix,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges() {
+        let v = vec![
+            ("1.2.3 - 2.3", false, false, ">=1.2.3 <2.4.0-0"),
+            (
+                ">= 1.2 < 2 || ~ 3",
+                false,
+                false,
+                ">=1.2.0 <2.0.0-0||>=3.0.0 <4.0.0-0",
+            ),
+            ("^v 1.2", true, false, ">=1.2.0 <1.3.0-0"),
+            ("~> >1.2", false, false, ">=1.2.0 <1.3.0-0"),
+            ("1.2.31.2", true, false, "1.2.3-1.2"),
+            ("1.2.3beta", true, false, "1.2.3-beta"),
+            ("^1.2.3-03", true, false, ">=1.2.3-3 <2.0.0-0"),
+            ("1.2.3_ || 1.x", true, false, ">=1.0.0 <2.0.0-0"),
+            ("^1.2", false, true, ">=1.2.0-0 <2.0.0-0"),
+            ("1.2.3 - 2.3.4", false, true, ">=1.2.3-0 <2.3.5-0"),
+            ("1.2.3+b - 2", false, true, ">=1.2.3 <3.0.0-0"),
+            ("0.x", false, false, "<1.0.0-0"),
+            (">=0.0.0-0 <1.2.3", false, true, "<1.2.3"),
+            ("^1.2 ^1", false, false, ">=1.2.0 <2.0.0-0 >=1.0.0"),
+        ];
+        for (input, loose, include_prerelease, output) in v {
+            let res = range(input, loose, include_prerelease)
+                .unwrap()
+                .iter()
+                .map(|set| {
+                    set.iter()
+                        .map(|c| c.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                })
+                .collect::<Vec<_>>()
+                .join("||");
+            assert_eq!(output, res, "testing range: {}", input);
+        }
+
+        for (input, loose) in [
+            ("^v 1.2", false),
+            ("^", false),
+            ("~", true),
+            ("1.2.3_", true),
+        ]
+        .iter()
+        {
+            assert!(
+                range(input, *loose, false).is_err(),
+                "testing range: {}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn overflow() {
+        let v = vec![
+            "^18446744073709551615",
+            "^0.18446744073709551615",
+            "~1.18446744073709551615",
+            ">18446744073709551615",
+            "<=1.18446744073709551615",
+            "1.2.3 - 18446744073709551615",
+            "18446744073709551615.x",
+            "18446744073709551616.0.0",
+        ];
+        for input in v {
+            assert!(
+                range(input, false, false).is_err(),
+                "testing overflow: {}",
+                input
+            );
+        }
+
+        assert!(range("^18446744073709551614", false, false).is_ok());
+        assert!(range("1.2.3_", false, false).is_err());
+    }
+}
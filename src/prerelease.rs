@@ -0,0 +1,219 @@
+use crate::error::Error;
+use crate::expressions::PRERELEASE;
+use crate::util::numeric_identifier;
+
+use std::{fmt, slice};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The prerelease of a [Version](crate::Version), e.g. `beta.1` in `1.2.3-beta.1`.
+///
+/// Prereleases are ordered by comparing their identifiers one by one, a prerelease being lower
+/// than a longer one it's a prefix of, e.g. `alpha < alpha.1 < alpha.beta < beta < beta.2 < beta.11`.
+/// ## Example
+/// ```
+/// # use semver_rs::{Identifier, Prerelease, Error};
+/// let pre = Prerelease::new("beta.11")?;
+///
+/// assert_eq!(pre.identifiers(), &[Identifier::AlphaNumeric("beta".into()), Identifier::Numeric(11)]);
+/// assert!(pre > Prerelease::new("beta.2")?);
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Prerelease {
+    pub(crate) identifiers: Vec<Identifier>,
+}
+
+/// A single identifier of a [Prerelease](crate::Prerelease). Numeric identifiers always have
+/// a lower precedence than alphanumeric ones.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "String", from = "String")
+)]
+pub enum Identifier {
+    /// An identifier made of digits only, e.g. `11` in `beta.11`.
+    Numeric(u64),
+    /// Any other identifier, e.g. `beta` in `beta.11`. Numeric identifiers too large
+    /// for a `u64` are kept as alphanumeric ones, like node-semver does.
+    AlphaNumeric(String),
+}
+
+impl Prerelease {
+    /// Parses dot separated identifiers, e.g. `beta.1`.
+    pub fn new(input: &str) -> Result<Self, Error> {
+        match PRERELEASE.is_match(input) {
+            true => Ok(Prerelease::from_parsed(input)),
+            false => Err(Error::InvalidPrerelease(input.into())),
+        }
+    }
+
+    // For identifiers the parser already validated.
+    pub(crate) fn from_parsed(input: &str) -> Self {
+        Prerelease {
+            identifiers: input.split('.').map(Identifier::from).collect(),
+        }
+    }
+
+    pub fn identifiers(&self) -> &[Identifier] {
+        &self.identifiers
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, Identifier> {
+        self.identifiers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.identifiers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.identifiers.is_empty()
+    }
+
+    // `-0` is the lowest possible prerelease, e.g. `<1.2.3-0` matches nothing of `1.2.3`.
+    pub(crate) fn is_zero(&self) -> bool {
+        self.identifiers == [Identifier::Numeric(0)]
+    }
+}
+
+impl Identifier {
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Identifier::Numeric(_))
+    }
+
+    pub fn as_numeric(&self) -> Option<u64> {
+        match *self {
+            Identifier::Numeric(n) => Some(n),
+            Identifier::AlphaNumeric(_) => None,
+        }
+    }
+
+    pub fn as_alphanumeric(&self) -> Option<&str> {
+        match self {
+            Identifier::Numeric(_) => None,
+            Identifier::AlphaNumeric(s) => Some(s),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Prerelease {
+    type Item = &'a Identifier;
+    type IntoIter = slice::Iter<'a, Identifier>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<&str> for Identifier {
+    fn from(s: &str) -> Self {
+        match numeric_identifier(s) {
+            Some(n) => Identifier::Numeric(n),
+            None => Identifier::AlphaNumeric(s.into()),
+        }
+    }
+}
+
+impl From<String> for Identifier {
+    fn from(s: String) -> Self {
+        Identifier::from(s.as_str())
+    }
+}
+
+impl From<Identifier> for String {
+    fn from(identifier: Identifier) -> Self {
+        identifier.to_string()
+    }
+}
+
+impl fmt::Display for Prerelease {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, identifier) in self.identifiers.iter().enumerate() {
+            if i > 0 {
+                write!(f, ".")?;
+            }
+            write!(f, "{}", identifier)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{}", n),
+            Identifier::AlphaNumeric(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordering() {
+        // each prerelease is lower than the next one
+        let v = [
+            "1",
+            "2",
+            "11",
+            "4294967296",
+            "99999999999999999999",
+            "alpha",
+            "alpha.1",
+            "alpha.beta",
+            "beta",
+            "beta.2",
+            "beta.11",
+            "rc.1",
+        ];
+
+        for pair in v.windows(2) {
+            let a = Prerelease::new(pair[0]).unwrap();
+            let b = Prerelease::new(pair[1]).unwrap();
+            assert!(a < b, "testing ordering: {} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn identifiers() {
+        let pre = Prerelease::new("alpha.0.x-1.99999999999999999999").unwrap();
+        assert_eq!(
+            pre.identifiers(),
+            &[
+                Identifier::AlphaNumeric("alpha".into()),
+                Identifier::Numeric(0),
+                Identifier::AlphaNumeric("x-1".into()),
+                Identifier::AlphaNumeric("99999999999999999999".into()),
+            ]
+        );
+        assert_eq!(pre.to_string(), "alpha.0.x-1.99999999999999999999");
+        assert_eq!(pre.len(), 4);
+        assert_eq!(pre.iter().filter(|id| id.is_numeric()).count(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let pre = Prerelease::new("beta.11").unwrap();
+        let json = serde_json::to_string(&pre).unwrap();
+        assert_eq!(json, r#"["beta","11"]"#);
+        assert_eq!(serde_json::from_str::<Prerelease>(&json).unwrap(), pre);
+    }
+
+    #[test]
+    fn invalid() {
+        for input in ["", "beta..1", "beta.01", "bét", "beta+build"].iter() {
+            match Prerelease::new(input) {
+                Err(Error::InvalidPrerelease(s)) => assert_eq!(&s, input),
+                res => panic!("testing invalid: {} {:?}", input, res),
+            }
+        }
+    }
+}
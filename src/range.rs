@@ -1,18 +1,34 @@
-use crate::builder::{Builder, Options, Parseable};
-use crate::comparator::{Comparator, ComparatorPair};
+use crate::builder::{Builder, IntoOptionsMaybe, Options, Parseable};
+use crate::comparator::Comparator;
+use crate::compiled_range::CompiledRange;
 use crate::error::Error;
-use crate::expressions::{
-    COMPARATOR_LOOSE, COMP_REPLACE_CARETS, RANGE_HYPHEN, RANGE_HYPHEN_LOOSE, RANGE_OR,
-    RANGE_TRIM_CARET, RANGE_TRIM_OPERATORS, RANGE_TRIM_TILDE, SPLIT_SPACES,
-};
+use crate::expressions::{RANGE_TRIM_CARET, RANGE_TRIM_OPERATORS, RANGE_TRIM_TILDE, SPLIT_SPACES};
+use crate::interval::{self, VersionInterval};
+use crate::lint::{self, Lint};
 use crate::operator::Operator;
-use crate::util::{is_any_version, match_at_index};
-use crate::version::Version;
+use crate::parser;
+use crate::prerelease::Identifier;
+use crate::version::{AsVersion, Version};
 use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+/// The side of a [Range](crate::Range) a version is tested against by [Range::outside](crate::Range::outside).
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Direction {
+    /// Above the highest version the range allows.
+    High,
+    /// Below the lowest version the range allows.
+    Low,
+}
+
 /// A `version range` is a set of `comparators` which specify versions that satisfy the `range`.
 /// A comparator is composed of an operator and a version. The set of primitive operators is:
 ///
@@ -36,92 +52,65 @@ use serde::{Deserialize, Serialize};
 /// For example, the range `>=1.2.7 <1.3.0` would match the versions `1.2.7`, `1.2.8`, and `1.2.99`, but not the versions `1.2.6`, `1.3.0`, or `1.1.0`.
 ///
 /// The range `1.2.7 || >=1.2.9 <2.0.0` would match the versions `1.2.7`, `1.2.9`, and `1.4.6`, but not the versions `1.2.8` or `2.0.0`.
-#[derive(Debug)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+///
+/// Two ranges are equal when their desugared comparators are, regardless of the order of the comparators
+/// and of the sets or of repeated sets, e.g. `^1.2.3` and `>=1.2.3 <2.0.0-0 || ^1.2.3`.
+/// Use [Range::is_equivalent](crate::Range::is_equivalent) to check whether they match the same versions instead.
+#[derive(Clone, Debug)]
 pub struct Range {
     pub(crate) comparators: Vec<Vec<Comparator>>,
 
-    opts: Option<Options>,
+    pub(crate) opts: Option<Options>,
 }
 
 impl<'p> Parseable<'p> for Range {
     fn parse(range_input: &'p str, opts: Option<Options>) -> Result<Self, Error> {
-        let loose = opts.unwrap_or_default().loose;
+        let range = Range::parse_sets(range_input, opts)?;
 
-        if range_input.is_empty() {
-            return Ok(Range {
-                //TODO: Figure out how to make it work with only an empty vec to avoid allocating
-                comparators: vec![vec![]],
-                opts,
-            });
+        // node-semver parses the version of every desugared comparator, which enforces the limits on them
+        let opts = opts.unwrap_or_default();
+        for c in range.comparators.iter().flatten() {
+            opts.check_version(range_input, &c.version)?;
         }
 
-        let comparators = RANGE_OR
-            .split(range_input)
-            .map(move |range: &str| {
-                //1. trim the range
-                let range = range.trim();
-
-                //2. replace hyphens `1.2.3 - 1.2.4` => `>=1.2.3 <=1.2.4`
-                let range = if let Some(range) = Range::replace_hyphens(range, loose)? {
-                    range.to_string()
-                } else if let Some(range) = Range::replace_carets(range)? {
-                    range.to_string()
-                } else {
-                    //3. trim the spaces around operators `> 1.2.3 < 1.2.5` => `>1.2.3 <1.2.5`
-                    let range = Range::trim_operators(range);
-
-                    //4. trim spaces around the tilde operator `~ 1.2.3` => `~1.2.3`
-                    let range = Range::trim_tilde(&range);
-
-                    //5. trim spaces around the caret operator `^ 1.2.3` => `^1.2.3`
-                    let range = Range::trim_caret(&range);
-
-                    //6. trim all the spaces that are left `1.2.3  1.2.4` => `1.2.3 1.2.4`
-                    let range = Range::trim_spaces(&range);
+        Ok(range)
+    }
+}
 
-                    range.to_string()
-                };
+impl FromStr for Range {
+    type Err = Error;
 
-                let comparators_parsed = range
-                    .split(' ')
-                    .map(|c| Comparator::normalize(c, loose))
-                    .fold(String::new(), |acc, c| acc + &c + " ");
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Range::new(s).parse()
+    }
+}
 
-                let comparators_parsed = comparators_parsed.trim();
-                if comparators_parsed.is_empty() {
-                    return Ok(Some(vec![]));
-                }
+impl TryFrom<&str> for Range {
+    type Error = Error;
 
-                // TODO: this split should yield an array with one empty string inside
-                // when used on an empty string, just like in the original npm package.
-                // The condition above is a workaround atm
-
-                let comparators = SPLIT_SPACES
-                    .split(comparators_parsed)
-                    .filter(|c| {
-                        if loose {
-                            COMPARATOR_LOOSE.is_match(c)
-                        } else {
-                            true
-                        }
-                    })
-                    .map(|r| Comparator::new(r, opts))
-                    .collect::<Result<Vec<Comparator>, Error>>()?;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Range::new(s).parse()
+    }
+}
 
-                Ok(match comparators.is_empty() {
-                    true => None,
-                    false => Some(comparators),
-                })
-            })
-            .collect::<Result<Vec<Option<Vec<Comparator>>>, Error>>()?;
+impl Range {
+    fn parse_sets(range_input: &str, opts: Option<Options>) -> Result<Self, Error> {
+        let opts_or_default = opts.unwrap_or_default();
 
-        if comparators.is_empty() {
-            Err(Error::InvalidRange(range_input.into()))
-        } else {
-            let comparators = comparators.into_iter().flatten().collect();
-            Ok(Range { comparators, opts })
+        if range_input.is_empty() {
+            return Ok(Range {
+                //TODO: Figure out how to make it work with only an empty vec to avoid allocating
+                comparators: vec![vec![]],
+                opts,
+            });
         }
+
+        let comparators = Range::simplify_sets(parser::range(
+            range_input,
+            opts_or_default.loose,
+            opts_or_default.include_prerelease,
+        )?);
+        Ok(Range { comparators, opts })
     }
 }
 
@@ -131,7 +120,33 @@ impl<'p> Range {
         Builder::new(range)
     }
 
-    fn trim_spaces(range: &str) -> Cow<str> {
+    // Drops the sets that can't match anything, e.g. `<0.0.0-0`, when there are others.
+    // Like node-semver, a set matching any version, e.g. `*`, replaces the others, even though
+    // it doesn't match the prereleases they may allow, e.g. `* || ^1.2.3-beta`.
+    fn simplify_sets(mut sets: Vec<Vec<Comparator>>) -> Vec<Vec<Comparator>> {
+        let is_null = |set: &[Comparator]| set.iter().any(|c| c.is_null_set());
+        if sets.len() > 1 {
+            // keep the first one, in case they're all null sets
+            let first = sets[0].clone();
+            sets.retain(|set| !is_null(set));
+            if sets.is_empty() {
+                sets.push(first);
+            }
+        }
+
+        if sets.len() > 1 {
+            let any = sets
+                .iter()
+                .position(|set| set.iter().all(|c| c.version.is_any()));
+            if let Some(any) = any {
+                return vec![sets.swap_remove(any)];
+            }
+        }
+
+        sets
+    }
+
+    pub(crate) fn trim_spaces(range: &str) -> Cow<'_, str> {
         //the other regexes won't allocate if they don't match, however this one will always allocate
         //so we check whether there's a match
         if SPLIT_SPACES.is_match(range) {
@@ -151,254 +166,899 @@ impl<'p> Range {
         }
     }
 
-    fn trim_caret(range: &str) -> Cow<str> {
+    pub(crate) fn trim_caret(range: &str) -> Cow<'_, str> {
         RANGE_TRIM_CARET.replace_all(range, "$1^")
     }
 
-    fn trim_tilde(range: &str) -> Cow<str> {
+    pub(crate) fn trim_tilde(range: &str) -> Cow<'_, str> {
         RANGE_TRIM_TILDE.replace_all(range, "$1~")
     }
 
-    fn trim_operators(range: &str) -> Cow<str> {
+    pub(crate) fn trim_operators(range: &str) -> Cow<'_, str> {
         RANGE_TRIM_OPERATORS.replace_all(range, "$1$2$3")
     }
 
-    fn replace_hyphens(range: &str, loose: bool) -> Result<Option<ComparatorPair>, Error> {
-        let mut caps = match loose {
-            true => RANGE_HYPHEN_LOOSE.captures_iter(range),
-            false => RANGE_HYPHEN.captures_iter(range),
-        };
-        let cap = match caps.next() {
-            Some(cap) => cap,
-            None => return Ok(None),
-        };
+    /// Looks for common mistakes in a range, e.g. `=>1.2.3` or `>=1.0, <2.0`, and suggests how to fix them.
+    /// The `input` doesn't need to be a valid range. Loose [Options](crate::Options) don't report leading `v`s.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{LintKind, Range};
+    /// let lints = Range::lint("=> 1.2.3, <2.0.0", None);
+    ///
+    /// assert_eq!(lints[0].kind, LintKind::ReversedOperator);
+    /// assert_eq!(lints[1].kind, LintKind::CommaSeparator);
+    /// assert_eq!(lints[1].suggestion, ">=1.2.3 <2.0.0");
+    /// ```
+    pub fn lint(input: &str, opts: impl IntoOptionsMaybe) -> Vec<Lint> {
+        lint::lint(input, opts.into().unwrap_or_default().loose)
+    }
 
-        let from = match_at_index(&cap, 1);
-        let from_major = match_at_index(&cap, 2);
-        let from_minor = match_at_index(&cap, 3);
-        let from_patch = match_at_index(&cap, 4);
+    pub(crate) fn include_prerelease(&self) -> bool {
+        self.opts.unwrap_or_default().include_prerelease
+    }
 
-        let comparator_from = if is_any_version(from_major) {
-            Comparator::empty()
-        } else if is_any_version(from_minor) {
-            Comparator::from_parts(
-                Operator::Gte,
-                Version::from_parts(from_major.parse()?, 0, 0, None),
+    /// Tests whether a `version` is in this `range`.
+    pub fn test(&self, version: &Version) -> bool {
+        let include_prerelease = self.include_prerelease();
+
+        self.comparators
+            .iter()
+            .any(move |comparators| Range::test_set(comparators, version, include_prerelease))
+    }
+
+    /// Precomputes this `range` into a [CompiledRange](crate::CompiledRange), for when lots of versions
+    /// are tested against it.
+    pub fn compile(&self) -> CompiledRange {
+        CompiledRange::new(self)
+    }
+
+    /// Yields the `versions` that satisfy this `range`, lazily parsing strings with its [Options](crate::Options).
+    /// Versions that fail to parse are skipped, and their errors pushed to `errors` when given.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new("^1.2.0").parse()?;
+    /// let mut errors = vec![];
+    /// let versions: Vec<_> = range
+    ///     .filter_satisfying(vec!["1.1.0", "1.2.3", "nope", "1.9.0", "2.0.0"], Some(&mut errors))
+    ///     .collect();
+    ///
+    /// assert_eq!(versions, ["1.2.3", "1.9.0"]);
+    /// assert_eq!(errors.len(), 1);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn filter_satisfying<'a, I>(
+        &'a self,
+        versions: I,
+        mut errors: Option<&'a mut Vec<Error>>,
+    ) -> impl Iterator<Item = I::Item> + 'a
+    where
+        I: IntoIterator,
+        I::IntoIter: 'a,
+        I::Item: AsVersion,
+    {
+        versions
+            .into_iter()
+            .filter(move |v| self.test_any(v, errors.as_deref_mut()) == Some(true))
+    }
+
+    /// Splits the `versions` into the ones that satisfy this `range` and the ones that don't,
+    /// skipping the ones that fail to parse like [Range::filter_satisfying](crate::Range::filter_satisfying) does.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Version, Error};
+    /// let range = Range::new("~1.2.0").parse()?;
+    /// let versions = vec![Version::new("1.2.5").parse()?, Version::new("1.3.0").parse()?];
+    /// let (satisfying, other) = range.partition(&versions, None);
+    ///
+    /// assert_eq!(satisfying, [&versions[0]]);
+    /// assert_eq!(other, [&versions[1]]);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn partition<I>(
+        &self,
+        versions: I,
+        mut errors: Option<&mut Vec<Error>>,
+    ) -> (Vec<I::Item>, Vec<I::Item>)
+    where
+        I: IntoIterator,
+        I::Item: AsVersion,
+    {
+        let mut satisfying = vec![];
+        let mut other = vec![];
+        for v in versions {
+            match self.test_any(&v, errors.as_deref_mut()) {
+                Some(true) => satisfying.push(v),
+                Some(false) => other.push(v),
+                None => {}
+            }
+        }
+
+        (satisfying, other)
+    }
+
+    /// Counts the `versions` that satisfy this `range`,
+    /// skipping the ones that fail to parse like [Range::filter_satisfying](crate::Range::filter_satisfying) does.
+    pub fn count_satisfying<I>(&self, versions: I, errors: Option<&mut Vec<Error>>) -> usize
+    where
+        I: IntoIterator,
+        I::Item: AsVersion,
+    {
+        self.filter_satisfying(versions, errors).count()
+    }
+
+    // Tests a version that may still need parsing, `None` when it fails to.
+    fn test_any(&self, version: &impl AsVersion, errors: Option<&mut Vec<Error>>) -> Option<bool> {
+        match version.as_version(self.opts) {
+            Ok(v) => Some(self.test(&v)),
+            Err(err) => {
+                if let Some(errors) = errors {
+                    errors.push(err);
+                }
+                None
+            }
+        }
+    }
+
+    /// Tests whether a `version` satisfies every comparator of a single comparator set.
+    pub(crate) fn test_set(
+        comparators: &[Comparator],
+        version: &Version,
+        include_prerelease: bool,
+    ) -> bool {
+        for c in comparators.iter() {
+            if !c.test(version) {
+                return false;
+            }
+        }
+
+        if version.has_prerelease() && !include_prerelease {
+            // Find the set of versions that are allowed to have prereleases
+            // For example, ^1.2.3-pr.1 desugars to >=1.2.3-pr.1 <2.0.0
+            // That should allow `1.2.3-pr.2` to pass.
+            // However, `1.2.4-alpha.notready` should NOT be allowed,
+            // even though it's within the range set by the comparators.
+            for c in comparators.iter() {
+                let v = &c.version;
+                if v.is_any() {
+                    continue;
+                }
+
+                if v.has_prerelease()
+                    && version.major == v.major
+                    && version.minor == v.minor
+                    && version.patch == v.patch
+                {
+                    return true;
+                }
+            }
+
+            false
+        } else {
+            true
+        }
+    }
+
+    /// Tests whether there is at least one version that satisfies both this `range` and the `other` one.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new("^1.2.0").parse()?;
+    ///
+    /// assert!(range.intersects(&Range::new("1.5.x || 3.x").parse()?, None));
+    /// assert!(!range.intersects(&Range::new(">=2.0.0").parse()?, None));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn intersects(&self, other: &Range, opts: impl IntoOptionsMaybe) -> bool {
+        let include_prerelease = opts.into().unwrap_or_default().include_prerelease;
+
+        self.comparators.iter().any(|comparators| {
+            Range::is_satisfiable(comparators, include_prerelease)
+                && other.comparators.iter().any(|other_comparators| {
+                    Range::is_satisfiable(other_comparators, include_prerelease)
+                        && comparators.iter().all(|c| {
+                            other_comparators
+                                .iter()
+                                .all(|other| c.intersects_with(other, include_prerelease))
+                        })
+                })
+        })
+    }
+
+    // The comparators sorted within each set, and the sets sorted and deduplicated,
+    // so that the order in which they were written and repeated sets don't matter.
+    fn canonical(&self) -> Vec<Vec<&Comparator>> {
+        fn key(c: &Comparator) -> (&Version, bool, bool, &Operator, bool) {
+            (
+                &c.version,
+                c.version.any,
+                c.version.empty,
+                &c.operator,
+                c.empty,
             )
-        } else if is_any_version(from_patch) {
-            Comparator::from_parts(
+        }
+
+        let mut sets = self
+            .comparators
+            .iter()
+            .map(|set| {
+                let mut set = set.iter().collect::<Vec<_>>();
+                set.sort_by(|a, b| key(a).cmp(&key(b)));
+                set.dedup();
+                set
+            })
+            .collect::<Vec<_>>();
+        sets.sort_by(|a, b| a.iter().map(|c| key(c)).cmp(b.iter().map(|c| key(c))));
+        sets.dedup();
+        sets
+    }
+
+    // A comparator set is satisfiable when all of its comparators intersect with each other.
+    fn is_satisfiable(comparators: &[Comparator], include_prerelease: bool) -> bool {
+        comparators.iter().enumerate().all(|(i, c)| {
+            comparators[..i]
+                .iter()
+                .all(|other| c.intersects_with(other, include_prerelease))
+        })
+    }
+
+    /// Tests whether every version that satisfies this `range` also satisfies the `sup` one,
+    /// the same way the `subset` function of [node-semver](https://github.com/npm/node-semver#ranges) does.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new("^1.2.3").parse()?;
+    ///
+    /// assert!(range.subset(&Range::new("1.x").parse()?, None));
+    /// assert!(!range.subset(&Range::new("~1.2.3").parse()?, None));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn subset(&self, sup: &Range, opts: impl IntoOptionsMaybe) -> bool {
+        if self == sup {
+            return true;
+        }
+
+        let include_prerelease = opts.into().unwrap_or_default().include_prerelease;
+
+        let mut saw_non_null = false;
+        for sub in self.comparators.iter() {
+            let mut is_subset = false;
+            for dom in sup.comparators.iter() {
+                let res = Range::simple_subset(sub, dom, include_prerelease);
+                saw_non_null = saw_non_null || res.is_some();
+                if res == Some(true) {
+                    is_subset = true;
+                    break;
+                }
+            }
+
+            if !is_subset && saw_non_null {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    // Returns `None` when the `sub` set can't match anything and is thus a subset of everything.
+    fn simple_subset(
+        sub: &[Comparator],
+        dom: &[Comparator],
+        include_prerelease: bool,
+    ) -> Option<bool> {
+        if sub == dom {
+            return Some(true);
+        }
+
+        let is_any = |set: &[Comparator]| set.iter().all(|c| c.version.is_any());
+        let min_version = |pre: Option<&str>| {
+            vec![Comparator::from_parts(
                 Operator::Gte,
-                Version::from_parts(from_major.parse()?, from_minor.parse()?, 0, None),
-            )
-        } else {
-            Comparator::from_parts(Operator::Gte, Version::new(from).parse()?)
+                Version::from_parts(0, 0, 0, pre.map(|p| p.to_owned()), None),
+            )]
         };
 
-        let to = match_at_index(&cap, 7);
-        let to_major = match_at_index(&cap, 8);
-        let to_minor = match_at_index(&cap, 9);
-        let to_patch = match_at_index(&cap, 10);
-        let to_prerelease = match_at_index(&cap, 11);
-
-        let comparator_to = if is_any_version(to_major) {
-            Comparator::empty()
-        } else if is_any_version(to_minor) {
-            let mut to_major = to_major.parse()?;
-            to_major += 1;
-
-            Comparator::from_parts(Operator::Lt, Version::from_parts(to_major, 0, 0, None))
-        } else if is_any_version(to_patch) {
-            let mut to_minor = to_minor.parse()?;
-            to_minor += 1;
-            Comparator::from_parts(
-                Operator::Lt,
-                Version::from_parts(to_major.parse()?, to_minor, 0, None),
-            )
-        } else if !to_prerelease.is_empty() {
-            Comparator::from_parts(
-                Operator::Lte,
-                Version::from_parts(
-                    to_major.parse()?,
-                    to_minor.parse()?,
-                    to_patch.parse()?,
-                    Some(to_prerelease.to_string()),
-                ),
-            )
+        let sub_min;
+        let sub = if is_any(sub) {
+            if is_any(dom) {
+                return Some(true);
+            }
+            sub_min = match include_prerelease {
+                true => min_version(Some("0")),
+                false => min_version(None),
+            };
+            &sub_min[..]
         } else {
-            Comparator::from_parts(Operator::Lte, Version::new(to).parse()?)
+            sub
         };
 
-        Ok(Some(ComparatorPair(
-            Some(comparator_from),
-            Some(comparator_to),
-        )))
-    }
+        let dom_min;
+        let dom = if is_any(dom) {
+            if include_prerelease {
+                return Some(true);
+            }
+            dom_min = min_version(None);
+            &dom_min[..]
+        } else {
+            dom
+        };
 
-    fn replace_carets(range: &str) -> Result<Option<ComparatorPair>, Error> {
-        let mut caps = COMP_REPLACE_CARETS.captures_iter(range);
-        let cap = match caps.next() {
-            Some(cap) => cap,
-            None => return Ok(None),
+        let satisfies = |v: &Version, c: &Comparator| {
+            Range::test_set(std::slice::from_ref(c), v, include_prerelease)
         };
 
-        let major = match_at_index(&cap, 1);
-        let minor = match_at_index(&cap, 2);
-        let patch = match_at_index(&cap, 3);
-        let prerelease = match_at_index(&cap, 4);
-
-        let mut cmp = ComparatorPair(None, None);
-        if is_any_version(major) {
-            cmp.0 = Some(Comparator::empty());
-        } else if is_any_version(minor) {
-            let major = major.parse()?;
-            cmp.0 = Some(Comparator::from_parts(
-                Operator::Gte,
-                Version::from_parts(major, 0, 0, None),
-            ));
-            cmp.1 = Some(Comparator::from_parts(
-                Operator::Lt,
-                Version::from_parts(major + 1, 0, 0, None),
-            ));
-        } else if is_any_version(patch) {
-            let major = major.parse()?;
-            let minor = minor.parse()?;
-            cmp.0 = Some(Comparator::from_parts(
-                Operator::Gte,
-                Version::from_parts(major, minor, 0, None),
-            ));
-            if major == 0 {
-                cmp.1 = Some(Comparator::from_parts(
-                    Operator::Lt,
-                    Version::from_parts(major, minor + 1, 0, None),
-                ));
-            } else {
-                cmp.1 = Some(Comparator::from_parts(
-                    Operator::Lt,
-                    Version::from_parts(major + 1, 0, 0, None),
-                ));
+        let mut eq: Option<&Version> = None;
+        let mut gt: Option<&Comparator> = None;
+        let mut lt: Option<&Comparator> = None;
+        for c in sub.iter() {
+            match c.operator {
+                Operator::Gt | Operator::Gte => gt = Some(Range::higher_gt(gt, c)),
+                Operator::Lt | Operator::Lte => lt = Some(Range::lower_lt(lt, c)),
+                _ => match eq {
+                    Some(eq) if eq != &c.version => return None,
+                    _ => eq = Some(&c.version),
+                },
             }
-        } else if !prerelease.is_empty() {
-            //this unwrap will never panic since we already verified that we have at least
-            //one char in the string
-            let prerelease = if prerelease.starts_with('-') {
-                prerelease.to_string()
-            } else {
-                format!("-{}", prerelease)
-            };
+        }
 
-            let major = major.parse()?;
-            let minor = minor.parse()?;
-            let patch = patch.parse()?;
+        let mut gtlt_comp = None;
+        if let (Some(gt), Some(lt)) = (gt, lt) {
+            let comp = gt.version.cmp(&lt.version);
+            if comp == Ordering::Greater
+                || (comp == Ordering::Equal
+                    && (gt.operator != Operator::Gte || lt.operator != Operator::Lte))
+            {
+                return None;
+            }
+            gtlt_comp = Some(comp);
+        }
+        let gtlt_equal = gtlt_comp == Some(Ordering::Equal);
 
-            cmp.0 = Some(Comparator::from_parts(
-                Operator::Gte,
-                Version::from_parts(major, minor, patch, Some(prerelease)),
-            ));
-            if major == 0 {
-                if minor == 0 {
-                    cmp.1 = Some(Comparator::from_parts(
-                        Operator::Lt,
-                        Version::from_parts(major, minor, patch + 1, None),
-                    ));
-                } else {
-                    cmp.1 = Some(Comparator::from_parts(
-                        Operator::Lt,
-                        Version::from_parts(major, minor + 1, 0, None),
-                    ));
+        if let Some(eq) = eq {
+            if gt.is_some_and(|gt| !satisfies(eq, gt)) || lt.is_some_and(|lt| !satisfies(eq, lt)) {
+                return None;
+            }
+            return Some(dom.iter().all(|c| satisfies(eq, c)));
+        }
+
+        // if the subset has a prerelease, we need a comparator in the superset
+        // with the same tuple and a prerelease, or it's not a subset
+        let needs_pre = |v: &&Version| !include_prerelease && v.has_prerelease();
+        let mut need_dom_gt_pre = gt.map(|c| &c.version).filter(needs_pre);
+        let mut need_dom_lt_pre = lt.map(|c| &c.version).filter(needs_pre);
+        // exception: <1.2.3-0 is the same as <1.2.3
+        if let (Some(pre), Some(lt)) = (need_dom_lt_pre, lt) {
+            if lt.operator == Operator::Lt
+                && pre.prerelease.as_ref().is_some_and(|pre| pre.is_zero())
+            {
+                need_dom_lt_pre = None;
+            }
+        }
+        let same_tuple = |c: &Comparator, v: &Version| {
+            c.version.has_prerelease()
+                && c.version.major == v.major
+                && c.version.minor == v.minor
+                && c.version.patch == v.patch
+        };
+
+        let mut has_dom_gt = false;
+        let mut has_dom_lt = false;
+        for c in dom.iter() {
+            let c_gt = matches!(c.operator, Operator::Gt | Operator::Gte);
+            let c_lt = matches!(c.operator, Operator::Lt | Operator::Lte);
+            has_dom_gt = has_dom_gt || c_gt;
+            has_dom_lt = has_dom_lt || c_lt;
+
+            if let Some(gt) = gt {
+                if need_dom_gt_pre.is_some_and(|pre| same_tuple(c, pre)) {
+                    need_dom_gt_pre = None;
+                }
+                if c_gt {
+                    let higher = Range::higher_gt(Some(gt), c);
+                    if std::ptr::eq(higher, c) && !std::ptr::eq(higher, gt) {
+                        return Some(false);
+                    }
+                } else if gt.operator == Operator::Gte && !satisfies(&gt.version, c) {
+                    return Some(false);
                 }
-            } else {
-                cmp.1 = Some(Comparator::from_parts(
-                    Operator::Lt,
-                    Version::from_parts(major + 1, 0, 0, None),
-                ));
             }
-        } else {
-            let major = major.parse()?;
-            let minor = minor.parse()?;
-            let patch = patch.parse()?;
 
-            cmp.0 = Some(Comparator::from_parts(
-                Operator::Gte,
-                Version::from_parts(major, minor, patch, None),
-            ));
-            if major == 0 {
-                if minor == 0 {
-                    cmp.1 = Some(Comparator::from_parts(
-                        Operator::Lt,
-                        Version::from_parts(major, minor, patch + 1, None),
-                    ));
-                } else {
-                    cmp.1 = Some(Comparator::from_parts(
-                        Operator::Lt,
-                        Version::from_parts(major, minor + 1, 0, None),
-                    ));
+            if let Some(lt) = lt {
+                if need_dom_lt_pre.is_some_and(|pre| same_tuple(c, pre)) {
+                    need_dom_lt_pre = None;
+                }
+                if c_lt {
+                    let lower = Range::lower_lt(Some(lt), c);
+                    if std::ptr::eq(lower, c) && !std::ptr::eq(lower, lt) {
+                        return Some(false);
+                    }
+                } else if lt.operator == Operator::Lte && !satisfies(&lt.version, c) {
+                    return Some(false);
                 }
-            } else {
-                cmp.1 = Some(Comparator::from_parts(
-                    Operator::Lt,
-                    Version::from_parts(major + 1, 0, 0, None),
-                ));
+            }
+
+            if !c_gt && !c_lt && (lt.is_some() || gt.is_some()) && !gtlt_equal {
+                return Some(false);
             }
         }
 
-        Ok(Some(cmp))
+        // if there was a < or >, and nothing in the dom, then must be false
+        // UNLESS it was limited by another range in the other direction.
+        // E.g. >1.0.0 <1.0.1 is still a subset of <2.0.0
+        if gt.is_some() && has_dom_lt && lt.is_none() && !gtlt_equal {
+            return Some(false);
+        }
+        if lt.is_some() && has_dom_gt && gt.is_none() && !gtlt_equal {
+            return Some(false);
+        }
+
+        // we needed a prerelease range in a specific tuple, but didn't get one, e.g.
+        // >=1.2.3-pre is not a subset of >=1.0.0 because it includes prereleases in the 1.2.3 tuple
+        Some(need_dom_gt_pre.is_none() && need_dom_lt_pre.is_none())
     }
 
-    /// Tests whether a `version` is in this `range`.
-    pub fn test(&self, version: &Version) -> bool {
-        let include_prerelease = match self.opts {
-            Some(ref opts) => opts.include_prerelease,
-            None => false,
+    // >=1.2.3 is lower than >1.2.3
+    fn higher_gt<'c>(a: Option<&'c Comparator>, b: &'c Comparator) -> &'c Comparator {
+        let a = match a {
+            Some(a) => a,
+            None => return b,
         };
 
-        self.comparators.iter().any(move |comparators| {
-            for c in comparators.iter() {
-                if !c.test(version) {
-                    return false;
+        match a.version.cmp(&b.version) {
+            Ordering::Greater => a,
+            Ordering::Less => b,
+            Ordering::Equal if b.operator == Operator::Gt && a.operator == Operator::Gte => b,
+            Ordering::Equal => a,
+        }
+    }
+
+    // <=1.2.3 is higher than <1.2.3
+    fn lower_lt<'c>(a: Option<&'c Comparator>, b: &'c Comparator) -> &'c Comparator {
+        let a = match a {
+            Some(a) => a,
+            None => return b,
+        };
+
+        match a.version.cmp(&b.version) {
+            Ordering::Less => a,
+            Ordering::Greater => b,
+            Ordering::Equal if b.operator == Operator::Lt && a.operator == Operator::Lte => b,
+            Ordering::Equal => a,
+        }
+    }
+
+    /// Tests whether a `version` is outside of this `range` in the given `direction`,
+    /// i.e. higher than every version it allows for [Direction::High](crate::Direction::High)
+    /// or lower than every version it allows for [Direction::Low](crate::Direction::Low).
+    /// Returns `false` when the `version` satisfies the `range`, or when it falls in a gap between its comparator sets.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Direction, Range, Version, Error};
+    /// let range = Range::new("^1.2.0").parse()?;
+    /// let ver = Version::new("2.0.0").parse()?;
+    ///
+    /// assert!(range.outside(&ver, Direction::High));
+    /// assert!(!range.outside(&ver, Direction::Low));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn outside(&self, version: &Version, direction: Direction) -> bool {
+        if self.test(version) {
+            return false;
+        }
+
+        // `beyond` is the ordering of a version past the range in the given direction
+        let (beyond, op, op_eq) = match direction {
+            Direction::High => (Ordering::Greater, Operator::Gt, Operator::Gte),
+            Direction::Low => (Ordering::Less, Operator::Lt, Operator::Lte),
+        };
+
+        let zero = Comparator::from_parts(Operator::Gte, Version::from_parts(0, 0, 0, None, None));
+        for comparators in self.comparators.iter() {
+            // the comparators nearest to and furthest from the given direction
+            let mut high = &zero;
+            let mut low = &zero;
+            for (i, c) in comparators.iter().enumerate() {
+                let c = match c.version.is_any() {
+                    true => &zero,
+                    false => c,
+                };
+
+                if i == 0 {
+                    high = c;
+                    low = c;
+                } else if c.version.cmp(&high.version) == beyond {
+                    high = c;
+                } else if c.version.cmp(&low.version) == beyond.reverse() {
+                    low = c;
                 }
             }
 
-            if version.has_prerelease() && !include_prerelease {
-                // Find the set of versions that are allowed to have prereleases
-                // For example, ^1.2.3-pr.1 desugars to >=1.2.3-pr.1 <2.0.0
-                // That should allow `1.2.3-pr.2` to pass.
-                // However, `1.2.4-alpha.notready` should NOT be allowed,
-                // even though it's within the range set by the comparators.
-                for c in comparators.iter() {
-                    let v = &c.version;
-                    if v.is_any() {
-                        continue;
-                    }
+            // the range is unbounded in the given direction
+            if high.operator == op || high.operator == op_eq {
+                return false;
+            }
 
-                    if v.has_prerelease()
-                        && version.major == v.major
-                        && version.minor == v.minor
-                        && version.patch == v.patch
-                    {
-                        return true;
-                    }
-                }
+            // the version is not past the bound of the set nearest to it
+            let ord = version.cmp(&low.version);
+            let within = match low.operator {
+                Operator::Empty => ord != beyond,
+                ref o if *o == op => ord != beyond,
+                ref o if *o == op_eq => ord == beyond.reverse(),
+                _ => false,
+            };
+            if within {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Tests whether this `range` and the `other` one match exactly the same versions, e.g. `^1.2.3` and `>=1.2.3 <2.0.0-0`.
+    /// Ranges parsed with different `include_prerelease` [Options](crate::Options) are never equivalent.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new("1.x || >=1.5.0 <3.0.0").parse()?;
+    ///
+    /// assert!(range.is_equivalent(&Range::new(">=1.0.0 <3.0.0").parse()?));
+    /// assert!(!range.is_equivalent(&Range::new("1.x").parse()?));
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn is_equivalent(&self, other: &Range) -> bool {
+        if self.include_prerelease() != other.include_prerelease() {
+            return false;
+        } else if self.include_prerelease() {
+            return self.to_intervals() == other.to_intervals();
+        }
+
+        if interval::releases(&self.to_intervals()) != interval::releases(&other.to_intervals()) {
+            return false;
+        }
+
+        // prereleases are only matched within the tuples of the prerelease comparators,
+        // so the ranges have to agree on each of those tuples separately
+        self.comparators
+            .iter()
+            .chain(other.comparators.iter())
+            .flatten()
+            .filter(|c| c.version.has_prerelease())
+            .all(|c| {
+                let tuple = [interval::prereleases(&c.version)];
+                self.tuple_prereleases(&tuple) == other.tuple_prereleases(&tuple)
+            })
+    }
+
+    // The prereleases of a single tuple matched by this range.
+    fn tuple_prereleases(&self, tuple: &[VersionInterval]) -> Vec<VersionInterval> {
+        let intervals = self
+            .comparators
+            .iter()
+            .filter(|comparators| {
+                comparators
+                    .iter()
+                    .any(|c| c.version.has_prerelease() && tuple[0].contains(&c.version))
+            })
+            .flat_map(|comparators| {
+                interval::intersect(&VersionInterval::from_set(comparators), tuple)
+            })
+            .collect();
+
+        interval::normalize(intervals)
+    }
+
+    /// Returns a new range matching the versions that satisfy either this `range` or the `other` one.
+    /// The `other` range is matched with the `include_prerelease` [Options](crate::Options) of this one,
+    /// whose options the new range keeps.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new("^1.2.0").parse()?;
+    /// let other = Range::new(">=1.5.0 <3.0.0").parse()?;
+    ///
+    /// assert_eq!(range.union(&other).to_string(), ">=1.2.0 <3.0.0");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn union(&self, other: &Range) -> Range {
+        let tuples = Range::prerelease_tuples(&[self, other]);
+        let include_prerelease = self.include_prerelease();
+        self.with_intervals(
+            interval::union(
+                &self.matched_intervals(&tuples, include_prerelease),
+                &other.matched_intervals(&tuples, include_prerelease),
+            ),
+            &tuples,
+        )
+    }
+
+    /// Returns a new range matching the versions that satisfy both this `range` and the `other` one.
+    /// The `other` range is matched with the `include_prerelease` [Options](crate::Options) of this one,
+    /// whose options the new range keeps.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new("^1.2.0").parse()?;
+    /// let other = Range::new("1.5.x || >=1.8.0").parse()?;
+    ///
+    /// assert_eq!(range.intersect(&other).to_string(), ">=1.5.0 <1.6.0-0||>=1.8.0 <2.0.0-0");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn intersect(&self, other: &Range) -> Range {
+        let tuples = Range::prerelease_tuples(&[self, other]);
+        let include_prerelease = self.include_prerelease();
+        self.with_intervals(
+            interval::intersect(
+                &self.matched_intervals(&tuples, include_prerelease),
+                &other.matched_intervals(&tuples, include_prerelease),
+            ),
+            &tuples,
+        )
+    }
+
+    /// Returns a new range matching the versions that don't satisfy this `range`, with the same [Options](crate::Options).
+    ///
+    /// Unless prereleases are included, a range can only match the prereleases of the `[major, minor, patch]`
+    /// tuples of its prerelease comparators, so the complement leaves out the prereleases of the other tuples too,
+    /// e.g. neither `^1.2.0` nor its complement match `1.5.0-beta`.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new("^1.2.0").parse()?;
+    ///
+    /// assert_eq!(range.complement().to_string(), "<1.2.0||>=2.0.0-0");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn complement(&self) -> Range {
+        let tuples = Range::prerelease_tuples(&[self]);
+        self.with_intervals(
+            interval::complement(&self.matched_intervals(&tuples, self.include_prerelease())),
+            &tuples,
+        )
+    }
+
+    /// Returns the normalized intervals of versions this `range` matches, sorted from the lowest to the highest,
+    /// with overlapping and adjacent comparator sets merged. An empty list means no version can satisfy the range.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Bound, Range, Version, Error};
+    /// let range = Range::new("^1.2.0 || 1.5.x || 3.0.0").parse()?;
+    /// let intervals = range.to_intervals();
+    ///
+    /// assert_eq!(intervals.len(), 2);
+    /// assert_eq!(intervals[0].lower, Bound::Included(Version::new("1.2.0").parse()?));
+    /// assert_eq!(intervals[0].upper, Bound::Excluded(Version::new("2.0.0-0").parse()?));
+    /// assert_eq!(intervals[1].lower, intervals[1].upper);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn to_intervals(&self) -> Vec<VersionInterval> {
+        let include_prerelease = self.include_prerelease();
+        let mut intervals = interval::normalize(
+            self.comparators
+                .iter()
+                .flat_map(|comparators| VersionInterval::from_set(comparators))
+                .collect(),
+        );
+        for interval in intervals.iter_mut() {
+            interval.include_prerelease = include_prerelease;
+        }
+
+        intervals
+    }
+
+    // The tuples of the prerelease comparators of the `ranges`, the only ones whose prereleases they can match
+    // unless prereleases are included.
+    fn prerelease_tuples(ranges: &[&Range]) -> Vec<[u64; 3]> {
+        let mut tuples: Vec<_> = ranges
+            .iter()
+            .flat_map(|range| range.comparators.iter())
+            .flat_map(|comparators| interval::prerelease_tuples(comparators))
+            .collect();
+        tuples.sort_unstable();
+        tuples.dedup();
 
-                false
-            } else {
-                true
+        tuples
+    }
+
+    // The intervals of the versions this range matches the way `test` does with `include_prerelease`,
+    // out of the releases and the prereleases of the `tuples` when it isn't set.
+    fn matched_intervals(
+        &self,
+        tuples: &[[u64; 3]],
+        include_prerelease: bool,
+    ) -> Vec<VersionInterval> {
+        if include_prerelease {
+            return self.to_intervals();
+        }
+
+        interval::normalize(
+            self.comparators
+                .iter()
+                .flat_map(|comparators| interval::from_set_matching(comparators, tuples))
+                .collect(),
+        )
+    }
+
+    // Converts the intervals returned by `matched_intervals` back into a range with the same options.
+    fn with_intervals(&self, intervals: Vec<VersionInterval>, tuples: &[[u64; 3]]) -> Range {
+        let intervals = match self.include_prerelease() {
+            true => intervals,
+            false => interval::prerelease_sets(intervals, tuples),
+        };
+        let comparators = match intervals.is_empty() {
+            // nothing can satisfy the range
+            true => vec![vec![Comparator::from_parts(
+                Operator::Lt,
+                Version::from_parts(0, 0, 0, Some("0".into()), None),
+            )]],
+            false => intervals.iter().map(|i| i.to_comparators()).collect(),
+        };
+
+        Range {
+            comparators,
+            opts: self.opts,
+        }
+    }
+
+    /// Returns the lowest version that satisfies this `range`, or `None` if no version can satisfy it, e.g. `>4 <3`.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Error};
+    /// let range = Range::new(">1.2.3 || >=2.0.0-beta").parse()?;
+    ///
+    /// assert_eq!(range.min_version().unwrap().to_string(), "1.2.4");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn min_version(&self) -> Option<Version> {
+        let min = Version::from_parts(0, 0, 0, None, None);
+        if self.test(&min) {
+            return Some(min);
+        }
+
+        let min = Version::from_parts(0, 0, 0, Some("0".into()), None);
+        if self.test(&min) {
+            return Some(min);
+        }
+
+        // the lowest version of a set is its highest lower bound
+        let min = self
+            .comparators
+            .iter()
+            .filter_map(|comparators| {
+                comparators
+                    .iter()
+                    .filter(|c| !c.version.is_any())
+                    .filter_map(|c| match c.operator {
+                        Operator::Gt => {
+                            let mut v = c.version.clone();
+                            match v.prerelease {
+                                Some(ref mut pre) if !pre.is_empty() => {
+                                    pre.identifiers.push(Identifier::Numeric(0))
+                                }
+                                _ => v = v.next_release()?,
+                            }
+                            Some(v)
+                        }
+                        Operator::Gte | Operator::Eq | Operator::StrictEq | Operator::Empty => {
+                            Some(c.version.clone())
+                        }
+                        _ => None,
+                    })
+                    .max()
+            })
+            .min()?;
+
+        match self.test(&min) {
+            true => Some(min),
+            false => None,
+        }
+    }
+
+    /// Returns the highest of the `versions` that satisfies this `range`, or `None` if none of them does.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Range, Version, Error};
+    /// let range = Range::new("^1.2.0").parse()?;
+    /// let versions = ["1.2.0", "1.4.1", "2.0.0"]
+    ///     .iter()
+    ///     .map(|v| Version::new(v).parse())
+    ///     .collect::<Result<Vec<_>, _>>()?;
+    ///
+    /// assert_eq!(range.max_satisfying(&versions).unwrap().to_string(), "1.4.1");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn max_satisfying<'a, I>(&self, versions: I) -> Option<&'a Version>
+    where
+        I: IntoIterator<Item = &'a Version>,
+    {
+        versions
+            .into_iter()
+            .filter(|v| self.test(v))
+            .fold(None, |max, v| match max {
+                Some(max) if max >= v => Some(max),
+                _ => Some(v),
+            })
+    }
+
+    /// Returns the lowest of the `versions` that satisfies this `range`, or `None` if none of them does.
+    pub fn min_satisfying<'a, I>(&self, versions: I) -> Option<&'a Version>
+    where
+        I: IntoIterator<Item = &'a Version>,
+    {
+        versions
+            .into_iter()
+            .filter(|v| self.test(v))
+            .fold(None, |min, v| match min {
+                Some(min) if min <= v => Some(min),
+                _ => Some(v),
+            })
+    }
+}
+
+// Ranges are compared in their canonical form, see `Range::canonical`.
+// Only `include_prerelease` changes which versions a parsed range matches, `loose` is left out.
+impl PartialEq for Range {
+    fn eq(&self, other: &Range) -> bool {
+        self.canonical() == other.canonical()
+            && self.include_prerelease() == other.include_prerelease()
+    }
+}
+
+impl Eq for Range {}
+
+impl Hash for Range {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
+        self.include_prerelease().hash(state);
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, comparators) in self.comparators.iter().enumerate() {
+            if i > 0 {
+                write!(f, "||")?;
             }
-        })
+
+            let mut first = true;
+            for c in comparators.iter().filter(|c| !c.version.is_any()) {
+                if !first {
+                    write!(f, " ")?;
+                }
+                write!(f, "{}", c)?;
+                first = false;
+            }
+        }
+
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ParseErrorKind;
+    use crate::version_ref::VersionRef;
 
     #[test]
     fn replace_hyphens() {
-        let v = vec![("1.2.3 - 1.2.4", ">=1.2.3 <=1.2.4")];
+        let v = vec![
+            ("1.2.3 - 1.2.4", ">=1.2.3 <=1.2.4"),
+            ("1.2.3 - 2.3.4", ">=1.2.3 <=2.3.4"),
+            ("1.2 - 2.3", ">=1.2.0 <2.4.0-0"),
+            ("1.2 - 2.3.4", ">=1.2.0 <=2.3.4"),
+            ("1.2.3 - 2", ">=1.2.3 <3.0.0-0"),
+            ("1 - 2.3.x", ">=1.0.0 <2.4.0-0"),
+            ("* - 2", "<3.0.0-0"),
+            ("1.2.3 - *", ">=1.2.3"),
+            ("1.2.3-beta - 2.3.4-rc.1", ">=1.2.3-beta <=2.3.4-rc.1"),
+            ("1.2.3-beta - 2.3", ">=1.2.3-beta <2.4.0-0"),
+            ("1.2 - 2.3.4-rc.1", ">=1.2.0 <=2.3.4-rc.1"),
+            ("v1.2.3 - v2.3.4", ">=1.2.3 <=2.3.4"),
+            ("1.2.3+build - 2.3.4+build", ">=1.2.3 <=2.3.4"),
+        ];
         for v in v {
-            let res = Range::replace_hyphens(v.0, false).unwrap();
-            let comp = format!("{}", &res.unwrap());
-            assert!(!comp.contains('-'), "contains hyphen");
-            assert_eq!(comp, String::from(v.1));
+            let res = Range::new(v.0).parse().unwrap().to_string();
+            assert_eq!(res, String::from(v.1), "testing hyphen: {}", v.0);
         }
     }
 
@@ -440,10 +1100,397 @@ mod tests {
 
     #[test]
     fn replce_carets() {
-        let v = vec![("^1.2.3", ">=1.2.3 <2.0.0")];
+        let v = vec![
+            ("^1.2.3", ">=1.2.3 <2.0.0-0"),
+            ("^1.2", ">=1.2.0 <2.0.0-0"),
+            ("^1", ">=1.0.0 <2.0.0-0"),
+            ("^1.x", ">=1.0.0 <2.0.0-0"),
+            ("^0.2.3", ">=0.2.3 <0.3.0-0"),
+            ("^0.2", ">=0.2.0 <0.3.0-0"),
+            ("^0.x", "<1.0.0-0"),
+            ("^0.0.3", ">=0.0.3 <0.0.4-0"),
+            ("^0.0.x", "<0.1.0-0"),
+            ("^0.0", "<0.1.0-0"),
+            ("^1.2.3-beta", ">=1.2.3-beta <2.0.0-0"),
+            ("^0.0.1-beta", ">=0.0.1-beta <0.0.2-0"),
+            ("^0.1.2-rc.1", ">=0.1.2-rc.1 <0.2.0-0"),
+            ("^1.2.3+build", ">=1.2.3 <2.0.0-0"),
+        ];
         for v in v {
-            let res = Range::replace_carets(v.0).unwrap().unwrap();
-            assert_eq!(res.to_string(), String::from(v.1));
+            let res = Range::new(v.0).parse().unwrap().to_string();
+            assert_eq!(res, String::from(v.1), "testing caret: {}", v.0);
         }
     }
+
+    #[test]
+    fn invalid() {
+        // input, kind, span of the error, loose
+        let v = vec![
+            ("garbage", ParseErrorKind::IllegalCharacter, 0..1, false),
+            (
+                "1.2.3 || garbage",
+                ParseErrorKind::IllegalCharacter,
+                9..10,
+                false,
+            ),
+            (">=01.2.3", ParseErrorKind::LeadingZero, 2..4, false),
+            ("^1.2.3-beta.01", ParseErrorKind::LeadingZero, 12..14, false),
+            (
+                "1.2.3-beta..1",
+                ParseErrorKind::EmptyIdentifier,
+                11..12,
+                false,
+            ),
+            ("=>1.2.3", ParseErrorKind::InvalidOperator, 0..2, false),
+            (
+                ">=1.0.0 <=>2.0.0",
+                ParseErrorKind::InvalidOperator,
+                8..11,
+                false,
+            ),
+            ("!1.2.3", ParseErrorKind::InvalidOperator, 0..1, false),
+            ("1.2.3 - ", ParseErrorKind::DanglingHyphen, 6..7, false),
+            ("- 1.2.3", ParseErrorKind::DanglingHyphen, 0..1, false),
+            (
+                "1.2.3 - 2.3.4 - 5",
+                ParseErrorKind::IllegalCharacter,
+                14..15,
+                false,
+            ),
+            (
+                "1.2.3 -2.0.0",
+                ParseErrorKind::IllegalCharacter,
+                6..7,
+                false,
+            ),
+            ("1.2.3 |", ParseErrorKind::IllegalCharacter, 6..7, false),
+            (">=1.2.3 <", ParseErrorKind::MissingComponent, 9..9, false),
+            ("~1.2.3_", ParseErrorKind::IllegalCharacter, 6..7, false),
+            ("vv1.2.3", ParseErrorKind::IllegalCharacter, 1..2, false),
+            (
+                "1.2.3 - 2.x.y",
+                ParseErrorKind::IllegalCharacter,
+                12..13,
+                false,
+            ),
+            (
+                "1.2.3 - 2.3.4_",
+                ParseErrorKind::IllegalCharacter,
+                13..14,
+                false,
+            ),
+            (">=1.2.3 ^", ParseErrorKind::MissingComponent, 9..9, false),
+            ("~>", ParseErrorKind::MissingComponent, 2..2, false),
+            (
+                "garbage || =>1",
+                ParseErrorKind::IllegalCharacter,
+                0..1,
+                true,
+            ),
+            (
+                "^99999999999999999999.2",
+                ParseErrorKind::Overflow,
+                1..21,
+                false,
+            ),
+            (
+                "1.2.3 - 99999999999999999999",
+                ParseErrorKind::Overflow,
+                8..28,
+                true,
+            ),
+        ];
+
+        for (input, kind, span, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            match Range::new(input).with_options(opts).parse() {
+                Err(Error::Parse(err)) => {
+                    assert_eq!(err.input, input, "testing invalid: {}", input);
+                    assert_eq!(err.kind, kind, "testing invalid kind: {}", input);
+                    assert_eq!(err.span, span, "testing invalid span: {}", input);
+                }
+                res => panic!("testing invalid: {} {:?}", input, res),
+            }
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let long = format!("1.2.3-{}", "a".repeat(251));
+        let max_length = |input: &str| {
+            matches!(
+                Range::new(input).parse(),
+                Err(Error::TooLong {
+                    max_length: 256,
+                    ..
+                })
+            )
+        };
+        assert!(Range::new(&long[..256]).parse().is_ok());
+        assert!(max_length(&long));
+        assert!(max_length(&format!("^{}", long)));
+
+        let max_safe_integer = |input: &str| {
+            matches!(
+                Range::new(input).parse(),
+                Err(Error::TooLarge {
+                    number: 9_007_199_254_740_992,
+                    ..
+                })
+            )
+        };
+        assert!(Range::new("<=9007199254740991.0.0").parse().is_ok());
+        assert!(Range::new("9007199254740990").parse().is_ok());
+        assert!(max_safe_integer("^9007199254740991"));
+        assert!(max_safe_integer(">=1.2.3 || >9007199254740992.0.0"));
+        assert!(matches!(
+            Comparator::new(">9007199254740992.0.0", None),
+            Err(Error::TooLarge { .. })
+        ));
+
+        let opts = Options::builder()
+            .max_length(8)
+            .max_safe_integer(10)
+            .build();
+        assert!(Range::new("^1.2.3 || 9.x")
+            .with_options(opts)
+            .parse()
+            .is_ok());
+        assert!(Range::new("1.2.3-beta").with_options(opts).parse().is_err());
+        assert!(Range::new("^10").with_options(opts).parse().is_err());
+    }
+
+    #[test]
+    fn largest_components() {
+        let opts = Options::builder().max_safe_integer(u64::MAX).build();
+        let range = Range::new(">1.18446744073709551615.18446744073709551615")
+            .with_options(opts)
+            .parse()
+            .unwrap();
+        assert_eq!(range.min_version().unwrap().to_string(), "2.0.0");
+
+        let err = Range::new("^18446744073709551615")
+            .with_options(opts)
+            .parse();
+        match err {
+            Err(Error::Parse(err)) => {
+                assert_eq!(err.kind, ParseErrorKind::Overflow);
+                assert_eq!(err.span, 1..21);
+            }
+            res => panic!("unexpected {:?}", res),
+        }
+    }
+
+    #[test]
+    fn filter_satisfying() {
+        let v = vec![
+            // range, versions, satisfying, other, invalid
+            (
+                "^1.2.0",
+                "1.1.0 1.2.0 1.2.4-beta 1.9.9 2.0.0",
+                "1.2.0 1.9.9",
+                "1.1.0 1.2.4-beta 2.0.0",
+                0,
+            ),
+            (
+                "~1.2.3-beta",
+                "1.2.3-alpha 1.2.3-beta.2 1.2.3 1.2",
+                "1.2.3-beta.2 1.2.3",
+                "1.2.3-alpha",
+                1,
+            ),
+            ("*", "0.0.1 1.2.3-beta x 01.2.3", "0.0.1", "1.2.3-beta", 2),
+            ("<0.0.0", "0.0.0 1.0.0", "", "0.0.0 1.0.0", 0),
+        ];
+
+        for (range, versions, satisfying, other, invalid) in v {
+            let r = Range::new(range).parse().unwrap();
+            let versions: Vec<_> = versions.split(' ').collect();
+            let satisfying: Vec<_> = satisfying.split(' ').filter(|s| !s.is_empty()).collect();
+            let other: Vec<_> = other.split(' ').filter(|s| !s.is_empty()).collect();
+
+            let mut errors = vec![];
+            let filtered: Vec<_> = r
+                .filter_satisfying(&versions, Some(&mut errors))
+                .copied()
+                .collect();
+            assert_eq!(filtered, satisfying, "testing filter_satisfying: {}", range);
+            assert_eq!(
+                errors.len(),
+                invalid,
+                "testing filter_satisfying errors: {}",
+                range
+            );
+
+            let mut errors = vec![];
+            let (sat, oth) = r.partition(versions.iter().map(|s| s.to_string()), Some(&mut errors));
+            assert_eq!(sat, satisfying, "testing partition: {}", range);
+            assert_eq!(oth, other, "testing partition: {}", range);
+            assert_eq!(errors.len(), invalid, "testing partition errors: {}", range);
+
+            let parsed: Vec<_> = versions
+                .iter()
+                .filter_map(|s| Version::new(s).parse().ok())
+                .collect();
+            assert_eq!(
+                r.count_satisfying(&parsed, None),
+                satisfying.len(),
+                "testing count_satisfying: {}",
+                range
+            );
+        }
+    }
+
+    #[test]
+    fn filter_satisfying_options() {
+        // strings are parsed with the options of the range
+        let opts = Options::builder()
+            .loose(true)
+            .include_prerelease(true)
+            .build();
+        let r = Range::new(">=1.2.3").with_options(opts).parse().unwrap();
+        let versions = ["=1.2.4beta", "1.2.2", "v1.3.0"];
+        let (sat, other) = r.partition(versions.iter(), None);
+        assert_eq!(sat, [&"=1.2.4beta", &"v1.3.0"]);
+        assert_eq!(other, [&"1.2.2"]);
+
+        let refs: Vec<_> = versions
+            .iter()
+            .map(|s| VersionRef::new(s).with_options(opts).parse().unwrap())
+            .collect();
+        assert_eq!(r.count_satisfying(refs, None), 2);
+
+        let r = Range::new(">=1.2.3").parse().unwrap();
+        let mut errors = vec![];
+        assert_eq!(r.count_satisfying(versions.iter(), Some(&mut errors)), 1);
+        assert!(matches!(errors[..], [Error::Parse(_)]), "{:?}", errors);
+    }
+
+    #[test]
+    fn set_operations_match_test() {
+        let ranges = [
+            "*",
+            "^1.2.0",
+            "1.x || 3.x",
+            "~1.5.0-beta.2",
+            ">=1.2.3-alpha <2.0.0-rc.1",
+            "1.2.3-beta || 2.x",
+            "<1.5.0 || >=2.0.0-0",
+            ">1.2.3 <=1.5.0-beta.2",
+            "<0.0.0-0",
+        ];
+        let versions = [
+            "0.0.0-0",
+            "0.0.0",
+            "1.0.0",
+            "1.2.3-alpha",
+            "1.2.3-beta",
+            "1.2.3",
+            "1.2.4-0",
+            "1.2.4",
+            "1.5.0-alpha",
+            "1.5.0-beta.2",
+            "1.5.0-beta.3",
+            "1.5.0",
+            "1.9.9",
+            "2.0.0-0",
+            "2.0.0-rc.1",
+            "2.0.0-rc.2",
+            "2.0.0",
+            "3.0.0-beta",
+            "3.1.0",
+            "4.0.0-0",
+        ]
+        .iter()
+        .map(|v| Version::new(v).parse().unwrap())
+        .collect::<Vec<_>>();
+
+        for include_prerelease in [false, true].iter() {
+            let opts = Options::builder()
+                .include_prerelease(*include_prerelease)
+                .build();
+            let parse = |range: &str| Range::new(range).with_options(opts).parse().unwrap();
+            for a in ranges.iter().map(|a| parse(a)) {
+                let complement = a.complement();
+                let tuples = Range::prerelease_tuples(&[&a]);
+                for v in versions.iter() {
+                    // only the prereleases of the tuples of its prerelease comparators can satisfy a range
+                    let mentioned = *include_prerelease
+                        || !v.has_prerelease()
+                        || tuples.contains(&[v.major, v.minor, v.patch]);
+                    assert_eq!(
+                        complement.test(v),
+                        mentioned && !a.test(v),
+                        "testing complement: {} {} {}",
+                        a,
+                        v,
+                        include_prerelease
+                    );
+                }
+
+                for b in ranges.iter().map(|b| parse(b)) {
+                    let (union, intersection) = (a.union(&b), a.intersect(&b));
+                    for v in versions.iter() {
+                        assert_eq!(
+                            union.test(v),
+                            a.test(v) || b.test(v),
+                            "testing union: {} {} {} {}",
+                            a,
+                            b,
+                            v,
+                            include_prerelease
+                        );
+                        assert_eq!(
+                            intersection.test(v),
+                            a.test(v) && b.test(v),
+                            "testing intersect: {} {} {} {}",
+                            a,
+                            b,
+                            v,
+                            include_prerelease
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        let range: Range = "^1.2.3".parse().unwrap();
+        assert_eq!(range.to_string(), ">=1.2.3 <2.0.0-0");
+        assert_eq!(
+            Range::try_from("~1.2").unwrap().to_string(),
+            ">=1.2.0 <1.3.0-0"
+        );
+        assert!("1.2.3 -".parse::<Range>().is_err());
+        assert!(Range::try_from(">=>1").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let range = Range::new("^1.2.3 || 2.x").parse().unwrap();
+        let json = serde_json::to_string(&range).unwrap();
+        assert_eq!(json, r#"">=1.2.3 <2.0.0-0||>=2.0.0 <3.0.0-0""#);
+        assert_eq!(serde_json::from_str::<Range>(&json).unwrap(), range);
+        let any: Range = serde_json::from_str(r#""*""#).unwrap();
+        let json = serde_json::to_string(&any).unwrap();
+        assert_eq!(serde_json::from_str::<Range>(&json).unwrap(), any);
+        assert!(serde_json::from_str::<Range>(r#""1.2.3 -""#).is_err());
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Structured(#[serde(with = "crate::serde::structured")] Range);
+
+        let opts = Options::builder().include_prerelease(true).build();
+        let range = Range::new("<1.2.3 || *")
+            .with_options(opts)
+            .parse()
+            .unwrap();
+        let json = serde_json::to_string(&Structured(range.clone())).unwrap();
+        assert!(json.starts_with(r#"{"comparators":[["#), "{}", json);
+        let back: Structured = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.0, range);
+        assert_eq!(format!("{:?}", back.0.opts), format!("{:?}", range.opts));
+    }
 }
@@ -0,0 +1,59 @@
+use std::fmt;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The kind of release a [Version](crate::Version) can be incremented by,
+/// same as the release types of [node-semver](https://github.com/npm/node-semver#functions).
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ReleaseType {
+    Major,
+    Premajor,
+    Minor,
+    Preminor,
+    Patch,
+    Prepatch,
+    Prerelease,
+    Release,
+}
+
+impl ReleaseType {
+    /// Whether this is one of the `pre*` release types.
+    pub fn is_pre(&self) -> bool {
+        matches!(
+            self,
+            ReleaseType::Premajor
+                | ReleaseType::Preminor
+                | ReleaseType::Prepatch
+                | ReleaseType::Prerelease
+        )
+    }
+}
+
+impl fmt::Display for ReleaseType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            ReleaseType::Major => "major",
+            ReleaseType::Premajor => "premajor",
+            ReleaseType::Minor => "minor",
+            ReleaseType::Preminor => "preminor",
+            ReleaseType::Patch => "patch",
+            ReleaseType::Prepatch => "prepatch",
+            ReleaseType::Prerelease => "prerelease",
+            ReleaseType::Release => "release",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+/// The number a new prerelease identifier starts counting from when incrementing a [Version](crate::Version),
+/// e.g. `1.2.3-beta.0` vs `1.2.3-beta.1`. `Omitted` leaves the number out entirely, e.g. `1.2.3-beta`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IdentifierBase {
+    Zero,
+    One,
+    Omitted,
+}
@@ -0,0 +1,246 @@
+//! [Version]s and [Range]s (de)serialize as strings, e.g. `"1.2.3"` and `">=1.2.3 <2.0.0-0"`,
+//! deserializing with the default [Options](crate::Options). Use [structured] for their fields instead.
+//! ## Example
+//! ```
+//! use semver_rs::{Range, Version};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Package {
+//!     version: Version,
+//!     dependency: Range,
+//! }
+//!
+//! let package: Package = serde_json::from_str(r#"{"version": "1.2.3", "dependency": "^1.2.3"}"#)?;
+//! assert_eq!(package.dependency, Range::new(">=1.2.3 <2.0.0-0").parse()?);
+//! assert_eq!(
+//!     serde_json::to_string(&package)?,
+//!     r#"{"version":"1.2.3","dependency":">=1.2.3 <2.0.0-0"}"#
+//! );
+//! # Ok::<(), Box<dyn std::error::Error + 'static>>(())
+//! ```
+
+use crate::error::Error;
+use crate::range::Range;
+use crate::version::Version;
+use crate::version_ref::VersionRef;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(FromStrVisitor::new("a version, e.g. `1.2.3`"))
+    }
+}
+
+impl Serialize for VersionRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for Range {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Range {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(FromStrVisitor::new("a range, e.g. `^1.2.3`"))
+    }
+}
+
+// Parses a string with the default options.
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> FromStrVisitor<T> {
+    fn new(expecting: &'static str) -> Self {
+        FromStrVisitor {
+            expecting,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: FromStr<Err = Error>> Visitor<'_> for FromStrVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<T, E> {
+        v.parse().map_err(E::custom)
+    }
+}
+
+/// (De)serializes a [Version] or a [Range] field as the structure it's made of,
+/// keeping what the string form loses, e.g. the [Options](crate::Options) of a range.
+/// ## Example
+/// ```
+/// use semver_rs::{Options, Range};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Dependency {
+///     #[serde(with = "semver_rs::serde::structured")]
+///     range: Range,
+/// }
+///
+/// let opts = Options::builder().include_prerelease(true).build();
+/// let range = Range::new("^1.2.3").with_options(opts).parse()?;
+/// let json = serde_json::to_string(&Dependency { range: range.clone() })?;
+/// let dependency: Dependency = serde_json::from_str(&json)?;
+///
+/// assert_eq!(dependency.range, range);
+/// # Ok::<(), Box<dyn std::error::Error + 'static>>(())
+/// ```
+pub mod structured {
+    use crate::builder::Options;
+    use crate::comparator::Comparator;
+    use crate::operator::Operator;
+    use crate::prerelease::Prerelease;
+    use crate::range::Range;
+    use crate::version::Version;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// The types that can be (de)serialized by [structured](crate::serde::structured).
+    pub trait Structured: Sized + private::Sealed {
+        #[doc(hidden)]
+        fn serialize_structured<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+        #[doc(hidden)]
+        fn deserialize_structured<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error>;
+    }
+
+    mod private {
+        pub trait Sealed {}
+
+        impl Sealed for crate::Version {}
+        impl Sealed for crate::Range {}
+    }
+
+    pub fn serialize<T: Structured, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize_structured(serializer)
+    }
+
+    pub fn deserialize<'de, T: Structured, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        T::deserialize_structured(deserializer)
+    }
+
+    impl Structured for Version {
+        fn serialize_structured<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            VersionDef::serialize(self, serializer)
+        }
+
+        fn deserialize_structured<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            VersionDef::deserialize(deserializer)
+        }
+    }
+
+    impl Structured for Range {
+        fn serialize_structured<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            RangeDef::serialize(self, serializer)
+        }
+
+        fn deserialize_structured<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Self, D::Error> {
+            RangeDef::deserialize(deserializer)
+        }
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "Version")]
+    struct VersionDef {
+        major: u64,
+        minor: u64,
+        patch: u64,
+        prerelease: Option<Prerelease>,
+        #[serde(default)]
+        build: Vec<String>,
+        any: bool,
+        empty: bool,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "Comparator")]
+    struct ComparatorDef {
+        operator: Operator,
+        #[serde(with = "VersionDef")]
+        version: Version,
+        empty: bool,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(remote = "Range")]
+    struct RangeDef {
+        #[serde(with = "comparator_sets")]
+        comparators: Vec<Vec<Comparator>>,
+        opts: Option<Options>,
+    }
+
+    mod comparator_sets {
+        use super::*;
+
+        struct Set<'a>(&'a [Comparator]);
+        struct Ref<'a>(&'a Comparator);
+
+        #[derive(Deserialize)]
+        struct Owned(#[serde(with = "ComparatorDef")] Comparator);
+
+        impl Serialize for Set<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_seq(self.0.iter().map(Ref))
+            }
+        }
+
+        impl Serialize for Ref<'_> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                ComparatorDef::serialize(self.0, serializer)
+            }
+        }
+
+        pub(super) fn serialize<S: Serializer>(
+            sets: &[Vec<Comparator>],
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            serializer.collect_seq(sets.iter().map(|set| Set(set)))
+        }
+
+        pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Vec<Vec<Comparator>>, D::Error> {
+            let sets = Vec::<Vec<Owned>>::deserialize(deserializer)?;
+            Ok(sets
+                .into_iter()
+                .map(|set| set.into_iter().map(|c| c.0).collect())
+                .collect())
+        }
+    }
+}
@@ -1,56 +1,67 @@
-use std::{borrow::Cow, cmp::Ordering};
+use crate::prerelease::{Identifier, Prerelease};
 
-use regex::Captures;
+use std::cmp::Ordering;
 
-pub(crate) fn is_any_version(v: &str) -> bool {
-    v.is_empty() || v == "*" || unicase::eq(v, "x")
+pub(crate) fn is_numeric_identifier(v: &str) -> bool {
+    !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit())
 }
 
-pub(crate) fn match_at_index<'a>(v: &'a Captures, i: usize) -> &'a str {
-    v.get(i).map_or("", |v| v.as_str())
-}
-
-pub(crate) fn match_at_index_owned(v: &Captures, i: usize) -> String {
-    v.get(i).map_or(String::new(), |v| v.as_str().to_owned())
+// The value of a numeric identifier, `None` for alphanumeric ones and numbers too large for a `u64`.
+pub(crate) fn numeric_identifier(v: &str) -> Option<u64> {
+    match is_numeric_identifier(v) {
+        true => v.parse().ok(),
+        false => None,
+    }
 }
 
-pub(crate) fn increment_version(v: &str) -> usize {
-    v.parse::<usize>().unwrap() + 1
-}
+// Compares two identifiers the same way the `Ord` of an `Identifier` does, without allocating one.
+pub(crate) fn compare_identifiers<S: AsRef<str>>(a: S, b: S) -> Ordering {
+    let a = a.as_ref();
+    let b = b.as_ref();
 
-pub(crate) fn get_prerelease_prefix(prerelease: &str) -> &'static str {
-    if !prerelease.starts_with('-') {
-        "-"
-    } else {
-        ""
+    match (numeric_identifier(a), numeric_identifier(b)) {
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => a.cmp(b),
+        (Some(a), Some(b)) => a.cmp(&b),
     }
 }
 
-pub(crate) fn replacer<'a>(
-    func: impl Fn(&[String]) -> Cow<'a, str>,
-) -> impl Fn(&regex::Captures) -> Cow<'a, str> {
-    move |cap: &regex::Captures| {
-        let args: [String; 6] = [
-            match_at_index_owned(cap, 0),
-            match_at_index_owned(cap, 1),
-            match_at_index_owned(cap, 2),
-            match_at_index_owned(cap, 3),
-            match_at_index_owned(cap, 4),
-            match_at_index_owned(cap, 5),
-        ];
-
-        func(&args)
+// Compares two lists of identifiers one by one, the shorter list being lower when one is a prefix of the other.
+pub(crate) fn compare_identifier_lists<S: AsRef<str>>(
+    mut a: impl Iterator<Item = S>,
+    mut b: impl Iterator<Item = S>,
+) -> Ordering {
+    loop {
+        match (a.next(), b.next()) {
+            (Some(a), Some(b)) => match a.as_ref() == b.as_ref() {
+                true => continue,
+                false => return compare_identifiers(a, b),
+            },
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+        }
     }
 }
 
-pub(crate) fn compare_identifiers<S: AsRef<str>>(a: S, b: S) -> Ordering {
-    let a = a.as_ref();
-    let b = b.as_ref();
+// The length of a version without its build, e.g. `5` for `1.2.3`, without formatting it.
+pub(crate) fn version_len(
+    major: u64,
+    minor: u64,
+    patch: u64,
+    prerelease: Option<&Prerelease>,
+) -> usize {
+    let digits = |n: u64| n.checked_ilog10().map_or(1, |d| d as usize + 1);
+    let prerelease = prerelease.map_or(0, |pre| {
+        pre.iter()
+            .map(|id| match id {
+                Identifier::Numeric(n) => digits(*n),
+                Identifier::AlphaNumeric(s) => s.len(),
+            })
+            .sum::<usize>()
+            + pre.len()
+    });
 
-    match (a.parse::<i32>(), b.parse::<i32>()) {
-        (Ok(_), Err(_)) => Ordering::Less,
-        (Err(_), Ok(_)) => Ordering::Greater,
-        (Err(_), Err(_)) => a.cmp(b),
-        (Ok(a), Ok(b)) => a.cmp(&b),
-    }
+    digits(major) + digits(minor) + digits(patch) + 2 + prerelease
 }
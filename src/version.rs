@@ -1,54 +1,92 @@
-use crate::builder::{Builder, Options, Parseable};
+use crate::builder::{Builder, IntoOptionsMaybe, Options, Parseable};
 use crate::error::Error;
-use crate::expressions::{VERSION, VERSION_LOOSE};
-use crate::util::compare_identifiers;
-use std::hash::Hash;
+use crate::expressions::{PRERELEASE, PRERELEASE_LOOSE};
+use crate::parser;
+use crate::prerelease::{Identifier, Prerelease};
+use crate::release_type::{IdentifierBase, ReleaseType};
+use crate::util::{compare_identifier_lists, compare_identifiers};
 
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
 use std::{cmp::Ordering, fmt, str};
 
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
-
 /// A `version` is described by the `v2.0.0` specification found at [semver](https://semver.org/).
 ///
 /// A leading `=` or `v` character is stripped off and ignored.
-#[derive(Default, Clone, Debug, Hash, Eq, PartialEq)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+///
+/// Build metadata (`1.2.3+build.5`) is preserved, but as per the specification it is ignored when
+/// determining version precedence. Use [compare_build](crate::Version::compare_build) to take it into account.
+#[derive(Default, Clone, Debug)]
 pub struct Version {
-    pub major: i64,
-    pub minor: i64,
-    pub patch: i64,
-    pub prerelease: Option<Vec<String>>,
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub prerelease: Option<Prerelease>,
+    pub build: Vec<String>,
 
-    any: bool,
-    empty: bool,
+    pub(crate) any: bool,
+    pub(crate) empty: bool,
 }
 
 impl<'p> Parseable<'p> for Version {
     fn parse(comp: &'p str, opts: Option<Options>) -> Result<Self, Error> {
-        let loose = opts.unwrap_or_default().loose;
+        let opts = opts.unwrap_or_default();
+        opts.check_length(comp, comp.len())?;
 
-        let trimmed = comp.trim();
-        let cap = match loose {
-            true => VERSION_LOOSE.captures(trimmed),
-            false => VERSION.captures(trimmed),
-        };
-        let cap = match cap {
-            Some(cap) => cap,
-            None => return Ok(Version::empty()),
-        };
+        let v = parser::version(comp, opts.loose)?;
+        opts.check_components(comp, [v.major, v.minor, v.patch])?;
+        Ok(v)
+    }
+}
 
-        let v = if cap.get(1).map_or("", |v| v.as_str()).is_empty() {
-            Version::any()
-        } else {
-            let major = cap.get(1).map_or("0", |v| v.as_str());
-            let minor = cap.get(2).map_or("0", |v| v.as_str());
-            let patch = cap.get(3).map_or("0", |v| v.as_str());
-            let prerelease = cap.get(4).map(|v| v.as_str().to_owned());
-            Version::from_parts(major.parse()?, minor.parse()?, patch.parse()?, prerelease)
-        };
+impl str::FromStr for Version {
+    type Err = Error;
 
-        Ok(v)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Version::new(s).parse()
+    }
+}
+
+impl TryFrom<&str> for Version {
+    type Error = Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        Version::new(s).parse()
+    }
+}
+
+/// Something a [Version](crate::Version) can be had from, either already parsed or a string parsed on demand,
+/// which lets e.g. [Range::filter_satisfying](crate::Range::filter_satisfying) take both.
+pub trait AsVersion {
+    /// Returns the version, parsing it with the given `opts` when it's a string.
+    fn as_version(&self, opts: Option<Options>) -> Result<Cow<'_, Version>, Error>;
+}
+
+impl AsVersion for Version {
+    fn as_version(&self, _: Option<Options>) -> Result<Cow<'_, Version>, Error> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+impl AsVersion for str {
+    fn as_version(&self, opts: Option<Options>) -> Result<Cow<'_, Version>, Error> {
+        Version::new(self)
+            .with_options(opts)
+            .parse()
+            .map(Cow::Owned)
+    }
+}
+
+impl AsVersion for String {
+    fn as_version(&self, opts: Option<Options>) -> Result<Cow<'_, Version>, Error> {
+        self.as_str().as_version(opts)
+    }
+}
+
+impl<T: AsVersion + ?Sized> AsVersion for &T {
+    fn as_version(&self, opts: Option<Options>) -> Result<Cow<'_, Version>, Error> {
+        (**self).as_version(opts)
     }
 }
 
@@ -67,10 +105,11 @@ impl<'p> Version {
             minor: 0,
             patch: 0,
             prerelease: None,
+            build: vec![],
         }
     }
 
-    /// Constructs an empty Version. It's the equivalent of `Version::new("")`.
+    /// Constructs an empty Version, which is never matched by a [Range](crate::Range).
     pub fn empty() -> Self {
         Version {
             empty: true,
@@ -79,18 +118,22 @@ impl<'p> Version {
             minor: 0,
             patch: 0,
             prerelease: None,
+            build: vec![],
         }
     }
 
-    /// Constructs a version from its already parsed parts, e.g. `Version::from_parts(1, 2, 3, None)`.
-    pub fn from_parts(major: i64, minor: i64, patch: i64, prerelease: Option<String>) -> Self {
-        let prerelease = match prerelease {
-            Some(pre) => pre
-                .split('.')
-                .map(|s| s.to_owned())
-                .collect::<Vec<String>>()
-                .into(),
-            None => None,
+    /// Constructs a version from its already parsed parts, e.g. `Version::from_parts(1, 2, 3, None, None)`.
+    pub fn from_parts(
+        major: u64,
+        minor: u64,
+        patch: u64,
+        prerelease: Option<String>,
+        build: Option<String>,
+    ) -> Self {
+        let prerelease = prerelease.map(|pre| Prerelease::from_parsed(&pre));
+        let build = match build {
+            Some(build) => build.split('.').map(|s| s.to_owned()).collect(),
+            None => vec![],
         };
 
         Version {
@@ -98,6 +141,7 @@ impl<'p> Version {
             minor,
             patch,
             prerelease,
+            build,
             empty: false,
             any: false,
         }
@@ -118,6 +162,264 @@ impl<'p> Version {
         }
     }
 
+    pub fn has_build(&self) -> bool {
+        !self.build.is_empty()
+    }
+
+    /// Compares two versions like [Ord](std::cmp::Ord) does, but when they are otherwise equal
+    /// orders them by their build metadata, e.g. `1.2.3+build.2 > 1.2.3+build.1 > 1.2.3`.
+    pub fn compare_build(&self, other: &Self) -> Ordering {
+        match self.cmp(other) {
+            Ordering::Equal => compare_identifier_lists(self.build.iter(), other.build.iter()),
+            res => res,
+        }
+    }
+
+    /// Increments the version by the given [ReleaseType](crate::ReleaseType), the same way
+    /// [node-semver](https://github.com/npm/node-semver#functions) does, e.g.
+    /// `1.2.3-alpha.0` incremented by `prerelease` gives `1.2.3-alpha.1`.
+    ///
+    /// `identifier` is the prerelease identifier to use for the `pre*` release types, e.g. `beta` in `2.0.0-beta.0`,
+    /// while `identifier_base` is the number it starts from, `0` by default. Build metadata is dropped.
+    /// The `identifier` is validated as a loose prerelease when [Options.loose](crate::Options::loose) is set,
+    /// and is kept as a single identifier even when it contains dots, like node-semver does.
+    /// ## Example
+    /// ```
+    /// # use semver_rs::{Version, ReleaseType, Error};
+    /// let ver = Version::new("1.2.3").parse()?;
+    /// let ver = ver.inc(ReleaseType::Premajor, Some("beta"), None, None)?;
+    ///
+    /// assert_eq!(ver.to_string(), "2.0.0-beta.0");
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn inc(
+        &self,
+        release: ReleaseType,
+        identifier: Option<&str>,
+        identifier_base: Option<IdentifierBase>,
+        opts: impl IntoOptionsMaybe,
+    ) -> Result<Self, Error> {
+        if self.is_any() || self.is_empty() {
+            return Err(Error::InvalidIncrement(format!(
+                "invalid version: {}",
+                self
+            )));
+        }
+
+        let identifier = identifier.filter(|identifier| !identifier.is_empty());
+        let prerelease_re = match opts.into().unwrap_or_default().loose {
+            true => &*PRERELEASE_LOOSE,
+            false => &*PRERELEASE,
+        };
+        if release.is_pre() {
+            match identifier {
+                None if identifier_base == Some(IdentifierBase::Omitted) => {
+                    return Err(Error::InvalidIncrement("identifier is empty".into()));
+                }
+                Some(identifier) if !prerelease_re.is_match(identifier) => {
+                    return Err(Error::InvalidIncrement(format!(
+                        "invalid identifier: {}",
+                        identifier
+                    )));
+                }
+                _ => {}
+            }
+        }
+
+        let mut ver = self.clone().without_build();
+        match release {
+            ReleaseType::Premajor => {
+                ver.prerelease = None;
+                ver.patch = 0;
+                ver.minor = 0;
+                ver.major = inc("major", ver.major)?;
+                ver.inc_pre(identifier, identifier_base)?;
+            }
+            ReleaseType::Preminor => {
+                ver.prerelease = None;
+                ver.patch = 0;
+                ver.minor = inc("minor", ver.minor)?;
+                ver.inc_pre(identifier, identifier_base)?;
+            }
+            ReleaseType::Prepatch => {
+                ver.prerelease = None;
+                ver.patch = inc("patch", ver.patch)?;
+                ver.inc_pre(identifier, identifier_base)?;
+            }
+            ReleaseType::Prerelease => {
+                if !ver.has_prerelease() {
+                    ver.patch = inc("patch", ver.patch)?;
+                }
+                ver.inc_pre(identifier, identifier_base)?;
+            }
+            ReleaseType::Release => {
+                if !ver.has_prerelease() {
+                    return Err(Error::InvalidIncrement(format!(
+                        "version {} is not a prerelease",
+                        self
+                    )));
+                }
+                ver.prerelease = None;
+            }
+            ReleaseType::Major => {
+                // 1.0.0-5 bumps to 1.0.0, while 1.1.0-5 bumps to 2.0.0
+                if ver.minor != 0 || ver.patch != 0 || !ver.has_prerelease() {
+                    ver.major = inc("major", ver.major)?;
+                }
+                ver.minor = 0;
+                ver.patch = 0;
+                ver.prerelease = None;
+            }
+            ReleaseType::Minor => {
+                // 1.2.0-5 bumps to 1.2.0, while 1.2.1-5 bumps to 1.3.0
+                if ver.patch != 0 || !ver.has_prerelease() {
+                    ver.minor = inc("minor", ver.minor)?;
+                }
+                ver.patch = 0;
+                ver.prerelease = None;
+            }
+            ReleaseType::Patch => {
+                // 1.2.0-5 bumps to 1.2.0, while 1.2.0 bumps to 1.2.1
+                if !ver.has_prerelease() {
+                    ver.patch = inc("patch", ver.patch)?;
+                }
+                ver.prerelease = None;
+            }
+        }
+
+        Ok(ver)
+    }
+
+    /// Classifies the difference between two versions by the [ReleaseType](crate::ReleaseType) that separates them,
+    /// the same way [node-semver](https://github.com/npm/node-semver#functions) does, e.g. `1.2.3` and `1.3.0-pre` differ by a `preminor`.
+    /// Returns `None` when the versions are equal.
+    pub fn diff(&self, other: &Self) -> Option<ReleaseType> {
+        let (high, low) = match self.cmp(other) {
+            Ordering::Equal => return None,
+            Ordering::Greater => (self, other),
+            Ordering::Less => (other, self),
+        };
+        let high_has_pre = high.has_prerelease();
+        let low_has_pre = low.has_prerelease();
+
+        // going from a prerelease to a release needs some special casing
+        if low_has_pre && !high_has_pre {
+            // 1.0.0-1 to 1.0.0, 1.1.1 or 2.0.0 is always a major
+            if low.patch == 0 && low.minor == 0 {
+                return Some(ReleaseType::Major);
+            }
+
+            // 1.1.0-1 to 1.1.0 is a minor, 1.1.1-1 to 1.1.1 is a patch
+            if low.compare_main(high) == Ordering::Equal {
+                if low.minor != 0 && low.patch == 0 {
+                    return Some(ReleaseType::Minor);
+                }
+                return Some(ReleaseType::Patch);
+            }
+        }
+
+        let release = if self.major != other.major {
+            (ReleaseType::Major, ReleaseType::Premajor)
+        } else if self.minor != other.minor {
+            (ReleaseType::Minor, ReleaseType::Preminor)
+        } else if self.patch != other.patch {
+            (ReleaseType::Patch, ReleaseType::Prepatch)
+        } else {
+            // both versions are prereleases of the same release
+            return Some(ReleaseType::Prerelease);
+        };
+
+        match high_has_pre {
+            true => Some(release.1),
+            false => Some(release.0),
+        }
+    }
+
+    fn inc_pre(
+        &mut self,
+        identifier: Option<&str>,
+        identifier_base: Option<IdentifierBase>,
+    ) -> Result<(), Error> {
+        let omit_base = identifier_base == Some(IdentifierBase::Omitted);
+        let base = match identifier_base {
+            Some(IdentifierBase::One) => Identifier::Numeric(1),
+            _ => Identifier::Numeric(0),
+        };
+
+        let mut prerelease = self.prerelease.take().unwrap_or_default();
+        if prerelease.is_empty() {
+            prerelease.identifiers.push(base.clone());
+        } else {
+            // bump the last numeric identifier, e.g. 1.2.3-alpha.0.beta => 1.2.3-alpha.1.beta
+            let numeric = prerelease
+                .identifiers
+                .iter_mut()
+                .rev()
+                .find_map(|id| match id {
+                    Identifier::Numeric(n) => Some(n),
+                    Identifier::AlphaNumeric(_) => None,
+                });
+
+            match numeric {
+                Some(n) => {
+                    *n = n.checked_add(1).ok_or_else(|| {
+                        Error::InvalidIncrement(format!("identifier overflow: {}", n))
+                    })?
+                }
+                None => {
+                    if omit_base && identifier == Some(prerelease.to_string().as_str()) {
+                        return Err(Error::InvalidIncrement("identifier already exists".into()));
+                    }
+                    prerelease.identifiers.push(base.clone());
+                }
+            }
+        }
+
+        if let Some(identifier) = identifier {
+            // like node-semver the identifier stays a single one, even when dotted, e.g. `alpha.beta`,
+            // or a loose number with leading zeros, e.g. `01`
+            let first = match identifier.len() > 1 && identifier.starts_with('0') {
+                true => Identifier::AlphaNumeric(identifier.into()),
+                false => Identifier::from(identifier),
+            };
+
+            // 1.2.3-beta.1 bumped with `beta` keeps counting, anything else starts over
+            let ids = &prerelease.identifiers;
+            let same_identifier = compare_identifiers(ids[0].to_string().as_str(), identifier)
+                == Ordering::Equal
+                && ids.get(1).is_some_and(|id| id.is_numeric());
+
+            if !same_identifier {
+                prerelease = Prerelease {
+                    identifiers: vec![first],
+                };
+                if !omit_base {
+                    prerelease.identifiers.push(base);
+                }
+            }
+        }
+
+        self.prerelease = Some(prerelease);
+        Ok(())
+    }
+
+    // The release right after this version's, e.g. `1.2.4` for `1.2.3-beta`, rolling over to the next minor
+    // or major when the patch or minor can't be incremented. `None` when there is no higher release.
+    pub(crate) fn next_release(&self) -> Option<Self> {
+        let (major, minor, patch) = match (self.patch.checked_add(1), self.minor.checked_add(1)) {
+            (Some(patch), _) => (self.major, self.minor, patch),
+            (None, Some(minor)) => (self.major, minor, 0),
+            (None, None) => (self.major.checked_add(1)?, 0, 0),
+        };
+
+        Some(Version::from_parts(major, minor, patch, None, None))
+    }
+
+    pub(crate) fn without_build(mut self) -> Self {
+        self.build.clear();
+        self
+    }
+
     fn compare_main(&self, other: &Self) -> Ordering {
         let mut compare_result = self.major.cmp(&other.major);
         if let Ordering::Equal = compare_result {
@@ -135,47 +437,66 @@ impl<'p> Version {
             (Some(_), None) => Ordering::Less,
             (None, Some(_)) => Ordering::Greater,
             (None, None) => Ordering::Equal,
-            (Some(pre1), Some(pre2)) => {
-                let mut pre1 = pre1.iter();
-                let mut pre2 = pre2.iter();
-                loop {
-                    match (pre1.next().as_ref(), pre2.next().as_ref()) {
-                        (Some(a), Some(b)) => match a.eq(b) {
-                            true => continue,
-                            false => return compare_identifiers(a, b),
-                        },
-                        (None, None) => return Ordering::Equal,
-                        (None, Some(_)) => return Ordering::Less,
-                        (Some(_), None) => return Ordering::Greater,
-                    }
-                }
-            }
+            (Some(pre1), Some(pre2)) => pre1.cmp(pre2),
         }
     }
 }
 
+// Increments a version number, failing instead of overflowing.
+fn inc(component: &str, n: u64) -> Result<u64, Error> {
+    n.checked_add(1)
+        .ok_or_else(|| Error::InvalidIncrement(format!("{} version overflows: {}", component, n)))
+}
+
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if !self.is_empty() {
             let fmt = if let Some(ref prerelease) = self.prerelease {
                 format!(
                     "{}.{}.{}-{}",
-                    self.major,
-                    self.minor,
-                    self.patch,
-                    prerelease.join(".")
+                    self.major, self.minor, self.patch, prerelease
                 )
             } else {
                 format!("{}.{}.{}", self.major, self.minor, self.patch)
             };
 
             write!(f, "{}", fmt)?;
+
+            if self.has_build() {
+                write!(f, "+{}", self.build.join("."))?;
+            }
         }
 
         Ok(())
     }
 }
 
+// Build metadata is deliberately left out of equality and hashing, so that versions differing
+// only by their build are considered equal, consistently with `Ord`.
+impl PartialEq for Version {
+    fn eq(&self, other: &Version) -> bool {
+        self.major == other.major
+            && self.minor == other.minor
+            && self.patch == other.patch
+            && self.prerelease == other.prerelease
+            && self.any == other.any
+            && self.empty == other.empty
+    }
+}
+
+impl Eq for Version {}
+
+impl Hash for Version {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.major.hash(state);
+        self.minor.hash(state);
+        self.patch.hash(state);
+        self.prerelease.hash(state);
+        self.any.hash(state);
+        self.empty.hash(state);
+    }
+}
+
 impl PartialOrd for Version {
     fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -195,6 +516,7 @@ impl Ord for Version {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::ParseErrorKind;
 
     fn vec_compare<U, V>(va: &[U], vb: &[V]) -> bool
     where
@@ -206,7 +528,7 @@ mod tests {
     #[test]
     fn test_sort() {
         // Create a vector of semver_rs::Version
-        let mut input_versions_list = vec![
+        let mut input_versions_list = [
             "1.2.3-dev",
             "1.2.3-dev.1",
             "1.2.3-dev.cache",
@@ -235,4 +557,248 @@ mod tests {
         ];
         assert!(vec_compare(&output, &expected));
     }
+
+    #[test]
+    fn invalid() {
+        // input, kind, span of the error, loose
+        let v = vec![
+            ("", ParseErrorKind::MissingComponent, 0..0, false),
+            ("garbage", ParseErrorKind::IllegalCharacter, 0..1, false),
+            ("1", ParseErrorKind::MissingComponent, 1..1, false),
+            ("1.2", ParseErrorKind::MissingComponent, 3..3, false),
+            ("1.2.", ParseErrorKind::MissingComponent, 4..4, false),
+            ("1.2.x", ParseErrorKind::IllegalCharacter, 4..5, false),
+            ("01.2.3", ParseErrorKind::LeadingZero, 0..2, false),
+            ("1.02.3", ParseErrorKind::LeadingZero, 2..4, false),
+            ("=1.2.3", ParseErrorKind::IllegalCharacter, 0..1, false),
+            ("vv1.2.3", ParseErrorKind::IllegalCharacter, 1..2, false),
+            ("v=1.2.3", ParseErrorKind::IllegalCharacter, 1..2, false),
+            ("1.2.3-", ParseErrorKind::EmptyIdentifier, 6..6, false),
+            (
+                "1.2.3-beta..1",
+                ParseErrorKind::EmptyIdentifier,
+                11..12,
+                false,
+            ),
+            ("1.2.3-beta.01", ParseErrorKind::LeadingZero, 11..13, false),
+            ("1.2.3+", ParseErrorKind::EmptyIdentifier, 6..6, false),
+            ("1.2.3 4", ParseErrorKind::IllegalCharacter, 6..7, false),
+            ("1.2.3beta", ParseErrorKind::IllegalCharacter, 5..6, false),
+            ("  1.2.3_", ParseErrorKind::IllegalCharacter, 7..8, false),
+            ("1.2.3-bét", ParseErrorKind::IllegalCharacter, 7..9, false),
+            (
+                "99999999999999999999.0.0",
+                ParseErrorKind::Overflow,
+                0..20,
+                false,
+            ),
+            (">1.2.3", ParseErrorKind::IllegalCharacter, 0..1, true),
+            ("1.2.3_", ParseErrorKind::IllegalCharacter, 5..6, true),
+            (
+                "1.99999999999999999999.0",
+                ParseErrorKind::Overflow,
+                2..22,
+                true,
+            ),
+        ];
+
+        for (input, kind, span, loose) in v {
+            let opts = Options::builder().loose(loose).build();
+            match Version::new(input).with_options(opts).parse() {
+                Err(Error::Parse(err)) => {
+                    assert_eq!(err.input, input, "testing invalid: {}", input);
+                    assert_eq!(err.kind, kind, "testing invalid kind: {}", input);
+                    assert_eq!(err.span, span, "testing invalid span: {}", input);
+                }
+                res => panic!("testing invalid: {} {:?}", input, res),
+            }
+        }
+    }
+
+    #[test]
+    fn build() {
+        let v = vec![
+            ("1.2.3+sha.abc", "1.2.3+sha.abc", vec!["sha", "abc"]),
+            (
+                "1.2.3-beta.1+exp.sha.5114f85",
+                "1.2.3-beta.1+exp.sha.5114f85",
+                vec!["exp", "sha", "5114f85"],
+            ),
+            ("v1.2.3+build", "1.2.3+build", vec!["build"]),
+            ("1.2.3", "1.2.3", vec![]),
+        ];
+
+        for (input, output, build) in v {
+            let ver = Version::new(input).parse().unwrap();
+            assert_eq!(ver.to_string(), output, "testing build display: {}", input);
+            assert!(vec_compare(&ver.build, &build), "testing build: {}", input);
+        }
+    }
+
+    #[test]
+    fn build_ignored_in_precedence() {
+        let a = Version::new("1.2.3+build.1").parse().unwrap();
+        let b = Version::new("1.2.3+build.2").parse().unwrap();
+        let c = Version::new("1.2.3").parse().unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a, c);
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_build() {
+        // the first should be greater than the second
+        let v = vec![
+            ("1.2.3+build.2", "1.2.3+build.1"),
+            ("1.2.3+build", "1.2.3"),
+            ("1.2.3+build.10", "1.2.3+build.9"),
+            ("1.2.3+build.a", "1.2.3+build.5"),
+            ("1.2.3+build.1.1", "1.2.3+build.1"),
+            ("1.2.4", "1.2.3+build"),
+            ("1.2.3+a", "1.2.3-pre+b"),
+        ];
+
+        for (v1, v2) in v {
+            let a = Version::new(v1).parse().unwrap();
+            let b = Version::new(v2).parse().unwrap();
+            assert_eq!(
+                a.compare_build(&b),
+                Ordering::Greater,
+                "testing compare_build: {} {}",
+                v1,
+                v2
+            );
+            assert_eq!(
+                b.compare_build(&a),
+                Ordering::Less,
+                "testing compare_build: {} {}",
+                v2,
+                v1
+            );
+            assert_eq!(
+                a.compare_build(&a),
+                Ordering::Equal,
+                "testing compare_build: {} {}",
+                v1,
+                v1
+            );
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let long = format!("1.2.3-{}", "a".repeat(251));
+        assert!(Version::new(&long[..256]).parse().is_ok());
+        match Version::new(&long).parse() {
+            Err(Error::TooLong { input, max_length }) => {
+                assert_eq!(input, long);
+                assert_eq!(max_length, 256);
+            }
+            res => panic!("testing limits: {:?}", res),
+        }
+
+        assert!(Version::new("9007199254740991.0.0").parse().is_ok());
+        for input in [
+            "9007199254740992.0.0",
+            "1.9007199254740992.0",
+            "1.2.9007199254740992",
+        ]
+        .iter()
+        {
+            match Version::new(input).parse() {
+                Err(Error::TooLarge {
+                    number,
+                    max_safe_integer,
+                    ..
+                }) => {
+                    assert_eq!(number, 9_007_199_254_740_992, "testing limits: {}", input);
+                    assert_eq!(max_safe_integer, 9_007_199_254_740_991);
+                }
+                res => panic!("testing limits: {} {:?}", input, res),
+            }
+        }
+
+        let opts = Options::builder()
+            .loose(true)
+            .max_length(8)
+            .max_safe_integer(10)
+            .build();
+        assert!(Version::new("10.10.10").with_options(opts).parse().is_ok());
+        assert!(Version::new(" 1.2.3-beta")
+            .with_options(opts)
+            .parse()
+            .is_err());
+        assert!(Version::new("11.0.0").with_options(opts).parse().is_err());
+    }
+
+    #[test]
+    fn inc_dotted_identifier() {
+        // the dotted identifier is kept whole, so bumping it again keeps counting
+        let ver = Version::new("1.2.3").parse().unwrap();
+        let ver = ver
+            .inc(ReleaseType::Prerelease, Some("alpha.beta"), None, None)
+            .unwrap();
+        assert_eq!(ver.to_string(), "1.2.4-alpha.beta.0");
+        let ver = ver
+            .inc(ReleaseType::Prerelease, Some("alpha.beta"), None, None)
+            .unwrap();
+        assert_eq!(ver.to_string(), "1.2.4-alpha.beta.1");
+    }
+
+    #[test]
+    fn inc_overflow() {
+        let v = vec![
+            (u64::MAX, 0, 0, ReleaseType::Major),
+            (u64::MAX, 0, 0, ReleaseType::Premajor),
+            (1, u64::MAX, 0, ReleaseType::Minor),
+            (1, 2, u64::MAX, ReleaseType::Patch),
+            (1, 2, u64::MAX, ReleaseType::Prerelease),
+        ];
+
+        for (major, minor, patch, release) in v {
+            let ver = Version::from_parts(major, minor, patch, None, None);
+            match ver.inc(release, None, None, None) {
+                Err(Error::InvalidIncrement(_)) => {}
+                res => panic!("testing inc overflow: {} {:?} {:?}", ver, release, res),
+            }
+        }
+
+        let ver = Version::from_parts(1, 2, 3, Some(u64::MAX.to_string()), None);
+        assert!(ver.inc(ReleaseType::Prerelease, None, None, None).is_err());
+    }
+
+    #[test]
+    fn from_str() {
+        let v: Version = "v1.2.3-beta+build".parse().unwrap();
+        assert_eq!(v, Version::new("1.2.3-beta+build").parse().unwrap());
+        assert_eq!(Version::try_from("1.2.3").unwrap().to_string(), "1.2.3");
+        assert!("1.2".parse::<Version>().is_err());
+        assert!(Version::try_from("1.2.3beta").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let v = Version::new("1.2.3-beta.1+build.5").parse().unwrap();
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#""1.2.3-beta.1+build.5""#);
+        let back: Version = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.to_string(), v.to_string());
+
+        assert!(serde_json::from_str::<Version>(r#""1.2""#).is_err());
+        assert!(serde_json::from_str::<Version>("123").is_err());
+
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Structured(#[serde(with = "crate::serde::structured")] Version);
+
+        let json = serde_json::to_string(&Structured(v.clone())).unwrap();
+        assert!(
+            json.starts_with(r#"{"major":1,"minor":2,"patch":3,"#),
+            "{}",
+            json
+        );
+        let back: Structured = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.0.to_string(), v.to_string());
+    }
 }
@@ -0,0 +1,225 @@
+use crate::builder::{Builder, Options, Parseable};
+use crate::error::Error;
+use crate::parser;
+use crate::util::{compare_identifier_lists, numeric_identifier};
+use crate::version::{AsVersion, Version};
+
+use std::borrow::Cow;
+use std::hash::{Hash, Hasher};
+use std::{cmp::Ordering, fmt};
+
+/// A [Version](crate::Version) that borrows its prerelease and build identifiers from the parsed string
+/// instead of allocating them, for when lots of versions only need to be sorted and compared.
+/// It orders the same way as a [Version](crate::Version) does, ignoring build metadata.
+/// ## Example
+/// ```
+/// # use semver_rs::{VersionRef, Error};
+/// let mut versions = ["1.2.3", "1.2.3-beta.2", "1.2.3-beta.10"]
+///     .iter()
+///     .map(|v| VersionRef::new(v).parse())
+///     .collect::<Result<Vec<_>, _>>()?;
+/// versions.sort();
+///
+/// assert_eq!(versions[0].prerelease, Some("beta.2"));
+/// assert_eq!(versions[2].to_owned().to_string(), "1.2.3");
+/// # Ok::<(), Error>(())
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct VersionRef<'a> {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// The dot separated prerelease identifiers, e.g. `beta.1` in `1.2.3-beta.1`.
+    pub prerelease: Option<&'a str>,
+    /// The dot separated build identifiers, e.g. `build.5` in `1.2.3+build.5`.
+    pub build: Option<&'a str>,
+}
+
+impl<'p> Parseable<'p> for VersionRef<'p> {
+    fn parse(comp: &'p str, opts: Option<Options>) -> Result<Self, Error> {
+        let opts = opts.unwrap_or_default();
+        opts.check_length(comp, comp.len())?;
+
+        let v = parser::version_ref(comp, opts.loose)?;
+        opts.check_components(comp, [v.major, v.minor, v.patch])?;
+        Ok(v)
+    }
+}
+
+impl<'a> VersionRef<'a> {
+    /// Construct a new VersionRef e.g `1.2.4`.
+    pub fn new(ver: &'a str) -> Builder<'a, Self> {
+        Builder::new(ver)
+    }
+
+    pub fn has_prerelease(&self) -> bool {
+        self.prerelease.is_some()
+    }
+
+    pub fn has_build(&self) -> bool {
+        self.build.is_some()
+    }
+
+    /// Allocates the [Version](crate::Version) this borrows from its input.
+    pub fn to_owned(&self) -> Version {
+        Version::from_parts(
+            self.major,
+            self.minor,
+            self.patch,
+            self.prerelease.map(|pre| pre.into()),
+            self.build.map(|build| build.into()),
+        )
+    }
+
+    /// Compares two versions like [Ord](std::cmp::Ord) does, but when they are otherwise equal
+    /// orders them by their build metadata, see [Version::compare_build](crate::Version::compare_build).
+    pub fn compare_build(&self, other: &Self) -> Ordering {
+        match self.cmp(other) {
+            Ordering::Equal => compare_identifier_lists(
+                self.build.into_iter().flat_map(|b| b.split('.')),
+                other.build.into_iter().flat_map(|b| b.split('.')),
+            ),
+            res => res,
+        }
+    }
+}
+
+impl<'a> From<VersionRef<'a>> for Version {
+    fn from(v: VersionRef<'a>) -> Self {
+        v.to_owned()
+    }
+}
+
+impl AsVersion for VersionRef<'_> {
+    fn as_version(&self, _: Option<Options>) -> Result<Cow<'_, Version>, Error> {
+        Ok(Cow::Owned(self.to_owned()))
+    }
+}
+
+impl fmt::Display for VersionRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(prerelease) = self.prerelease {
+            write!(f, "-{}", prerelease)?;
+        }
+        if let Some(build) = self.build {
+            write!(f, "+{}", build)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Like for `Version`, build metadata is left out of equality and hashing to be consistent with `Ord`,
+// which also makes loose numeric identifiers with leading zeros equal, e.g. `1.2.3-01` and `1.2.3-1`.
+impl PartialEq for VersionRef<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for VersionRef<'_> {}
+
+impl Hash for VersionRef<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.major.hash(state);
+        self.minor.hash(state);
+        self.patch.hash(state);
+        self.prerelease.is_some().hash(state);
+        for identifier in self.prerelease.into_iter().flat_map(|pre| pre.split('.')) {
+            match numeric_identifier(identifier) {
+                Some(n) => n.hash(state),
+                None => identifier.hash(state),
+            }
+        }
+    }
+}
+
+impl PartialOrd for VersionRef<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VersionRef<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let main =
+            (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch));
+        match (main, self.prerelease, other.prerelease) {
+            (Ordering::Equal, Some(_), None) => Ordering::Less,
+            (Ordering::Equal, None, Some(_)) => Ordering::Greater,
+            (Ordering::Equal, Some(pre1), Some(pre2)) => {
+                compare_identifier_lists(pre1.split('.'), pre2.split('.'))
+            }
+            (main, _, _) => main,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_version() {
+        let v = [
+            "1.2.3",
+            "  v1.2.3-beta  ",
+            "1.2.3-beta.2",
+            "1.2.3-beta.10",
+            "1.2.3-beta.2+build.5",
+            "1.2.3-alpha",
+            "1.2.3-1",
+            "1.2.3+build",
+            "1.2.4",
+            "0.10.0",
+            "0.9.0",
+        ];
+
+        for a in v.iter() {
+            let a_ref = VersionRef::new(a).parse().unwrap();
+            let a_ver = Version::new(a).parse().unwrap();
+            assert_eq!(a_ref.to_owned(), a_ver, "testing to_owned: {}", a);
+            assert_eq!(
+                a_ref.to_string(),
+                a_ver.to_string(),
+                "testing display: {}",
+                a
+            );
+
+            for b in v.iter() {
+                let b_ref = VersionRef::new(b).parse().unwrap();
+                let b_ver = Version::new(b).parse().unwrap();
+                assert_eq!(
+                    a_ref.cmp(&b_ref),
+                    a_ver.cmp(&b_ver),
+                    "testing cmp: {} {}",
+                    a,
+                    b
+                );
+                assert_eq!(a_ref == b_ref, a_ver == b_ver, "testing eq: {} {}", a, b);
+                assert_eq!(
+                    a_ref.compare_build(&b_ref),
+                    a_ver.compare_build(&b_ver),
+                    "testing compare_build: {} {}",
+                    a,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn loose() {
+        let opts = Options::builder().loose(true).build();
+        let v = VersionRef::new("=1.2.3beta")
+            .with_options(opts)
+            .parse()
+            .unwrap();
+        assert_eq!(v.prerelease, Some("beta"));
+        assert_eq!(v.to_string(), "1.2.3-beta");
+
+        assert!(VersionRef::new("=1.2.3beta").parse().is_err());
+        assert!(VersionRef::new("1.2").parse().is_err());
+    }
+}